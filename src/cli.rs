@@ -1,4 +1,9 @@
 use clap::{Parser, Subcommand, ValueEnum};
+use clap_complete::{ArgValueCandidates, ArgValueCompleter};
+
+use crate::commands::complete::{
+    branch_candidates, hosting_candidates, project_candidates, selector_candidates, tag_candidates,
+};
 
 #[derive(Parser)]
 #[command(name = "pm")]
@@ -12,7 +17,7 @@ pub struct Cli {
     pub output: Option<OutputFormatArg>,
 
     /// Custom config file path
-    #[arg(short, long, global = true)]
+    #[arg(short, long, global = true, env = "PM_CONFIG")]
     pub config: Option<String>,
 
     /// Verbose output
@@ -42,6 +47,10 @@ pub enum Commands {
     #[command(visible_alias = "e")]
     Edit(EditArgs),
 
+    /// Move a project's checkout to a new path
+    #[command(visible_alias = "move")]
+    Mv(MvArgs),
+
     /// Show git status for projects
     #[command(visible_alias = "st")]
     Status(StatusArgs),
@@ -50,6 +59,13 @@ pub enum Commands {
     #[command(visible_alias = "pull")]
     Update(UpdateArgs),
 
+    /// Clone registered projects that are missing on disk
+    Clone(CloneArgs),
+
+    /// Run a command in each selected project
+    #[command(visible_alias = "x")]
+    Exec(ExecArgs),
+
     /// Search projects
     #[command(visible_aliases = &["find", "f"])]
     Search(SearchArgs),
@@ -58,11 +74,32 @@ pub enum Commands {
     #[command(visible_aliases = &["cd", "go"])]
     Navigate(NavigateArgs),
 
+    /// Register existing repositories found on disk
+    Import(ImportArgs),
+
+    /// Manage git worktrees of worktree projects
+    #[command(visible_alias = "wt")]
+    Worktree(WorktreeArgs),
+
     /// Initialize pm configuration
     Init(InitArgs),
 
+    /// Manage config file backups
+    Config(ConfigArgs),
+
     /// Generate shell completions
     Completions(CompletionsArgs),
+
+    /// Print shell integration (cd wrapper, completions, prompt segment)
+    ShellInit(ShellInitArgs),
+
+    /// Print completion candidates (used by shell integration)
+    #[command(name = "__complete", hide = true)]
+    Complete(CompleteArgs),
+
+    /// Print the project containing the current directory (used by shell prompts)
+    #[command(name = "__prompt", hide = true)]
+    Prompt,
 }
 
 #[derive(clap::Args)]
@@ -83,7 +120,7 @@ pub struct CreateArgs {
     pub worktree: bool,
 
     /// Hosting service
-    #[arg(short = 'H', long)]
+    #[arg(short = 'H', long, add = ArgValueCandidates::new(hosting_candidates))]
     pub hosting: Option<String>,
 
     /// Custom local path
@@ -93,12 +130,24 @@ pub struct CreateArgs {
     /// Clone repository after creation
     #[arg(long)]
     pub clone: bool,
+
+    /// Checkout layout for worktree projects (defaults to the hosting setting)
+    #[arg(long, value_enum)]
+    pub layout: Option<WorktreeLayoutArg>,
+
+    /// Tag the project (repeatable)
+    #[arg(short, long = "tag", value_name = "TAG", add = ArgValueCandidates::new(tag_candidates))]
+    pub tags: Vec<String>,
 }
 
 #[derive(clap::Args)]
 pub struct ListArgs {
+    /// Project selector, e.g. `hosting:github tag:infra name:api-* !worktree dirty`
+    #[arg(value_name = "SELECTOR", add = ArgValueCompleter::new(selector_candidates))]
+    pub selector: Vec<String>,
+
     /// Filter by hosting
-    #[arg(short = 'H', long)]
+    #[arg(short = 'H', long, add = ArgValueCandidates::new(hosting_candidates))]
     pub hosting: Option<String>,
 
     /// Show only worktrees
@@ -112,11 +161,16 @@ pub struct ListArgs {
     /// Filter by name/description
     #[arg(short, long)]
     pub search: Option<String>,
+
+    /// Show only projects with this tag (repeatable, all must match)
+    #[arg(short, long = "tag", value_name = "TAG", add = ArgValueCandidates::new(tag_candidates))]
+    pub tags: Vec<String>,
 }
 
 #[derive(clap::Args)]
 pub struct DeleteArgs {
     /// Project name
+    #[arg(add = ArgValueCandidates::new(project_candidates))]
     pub name: String,
 
     /// Skip confirmation
@@ -135,16 +189,25 @@ pub struct DeleteArgs {
 #[derive(clap::Args)]
 pub struct EditArgs {
     /// Project name
+    #[arg(add = ArgValueCandidates::new(project_candidates))]
     pub name: String,
 
     /// Update description
     #[arg(short, long)]
     pub description: Option<String>,
 
-    /// Update repository URL
+    /// Update repository URL (also recomputes hosting and local path)
     #[arg(short, long)]
     pub repo: Option<String>,
 
+    /// Move to another hosting (also recomputes the local path)
+    #[arg(short = 'H', long, add = ArgValueCandidates::new(hosting_candidates))]
+    pub hosting: Option<String>,
+
+    /// Also point the repository's origin remote at the new URL
+    #[arg(long, requires = "repo")]
+    pub update_remote: bool,
+
     /// Mark as worktree
     #[arg(short, long)]
     pub worktree: bool,
@@ -160,16 +223,46 @@ pub struct EditArgs {
     /// Update local path
     #[arg(short, long)]
     pub path: Option<String>,
+
+    /// Move the checkout on disk when the local path changes
+    #[arg(long = "move")]
+    pub move_files: bool,
+
+    /// Add a tag (repeatable)
+    #[arg(long = "add-tag", value_name = "TAG", add = ArgValueCandidates::new(tag_candidates))]
+    pub add_tags: Vec<String>,
+
+    /// Set how `pm update` integrates upstream changes for this project
+    #[arg(long, value_enum, value_name = "STRATEGY")]
+    pub update_strategy: Option<UpdateStrategyArg>,
+
+    /// Remove a tag (repeatable)
+    #[arg(long = "remove-tag", value_name = "TAG", add = ArgValueCandidates::new(tag_candidates))]
+    pub remove_tags: Vec<String>,
+}
+
+#[derive(clap::Args)]
+pub struct MvArgs {
+    /// Project name
+    #[arg(add = ArgValueCandidates::new(project_candidates))]
+    pub project: String,
+
+    /// New local path for the checkout
+    pub new_path: String,
 }
 
 #[derive(clap::Args)]
 pub struct StatusArgs {
+    /// Project selector, e.g. `hosting:github tag:infra name:api-* !worktree dirty`
+    #[arg(value_name = "SELECTOR", add = ArgValueCompleter::new(selector_candidates))]
+    pub selector: Vec<String>,
+
     /// Filter by hosting
-    #[arg(short = 'H', long)]
+    #[arg(short = 'H', long, add = ArgValueCandidates::new(hosting_candidates))]
     pub hosting: Option<String>,
 
     /// Check specific project
-    #[arg(short, long)]
+    #[arg(short, long, add = ArgValueCandidates::new(project_candidates))]
     pub name: Option<String>,
 
     /// Show all projects (default)
@@ -179,16 +272,40 @@ pub struct StatusArgs {
     /// Show only dirty projects
     #[arg(long)]
     pub dirty: bool,
+
+    /// Show only projects with commits not pushed to upstream
+    #[arg(long)]
+    pub unpushed: bool,
+
+    /// Show only projects behind their upstream
+    #[arg(long)]
+    pub behind: bool,
+
+    /// Number of repositories to inspect concurrently (defaults to CPU count)
+    #[arg(short, long)]
+    pub jobs: Option<usize>,
+
+    /// Check the worktree of this branch instead of the default branch
+    #[arg(short, long, add = ArgValueCandidates::new(branch_candidates))]
+    pub branch: Option<String>,
+
+    /// Only include projects with this tag (repeatable, all must match)
+    #[arg(short, long = "tag", value_name = "TAG", add = ArgValueCandidates::new(tag_candidates))]
+    pub tags: Vec<String>,
 }
 
 #[derive(clap::Args)]
 pub struct UpdateArgs {
+    /// Project selector, e.g. `hosting:github tag:infra name:api-* !worktree dirty`
+    #[arg(value_name = "SELECTOR", add = ArgValueCompleter::new(selector_candidates))]
+    pub selector: Vec<String>,
+
     /// Filter by hosting
-    #[arg(short = 'H', long)]
+    #[arg(short = 'H', long, add = ArgValueCandidates::new(hosting_candidates))]
     pub hosting: Option<String>,
 
     /// Update specific project
-    #[arg(short, long)]
+    #[arg(short, long, add = ArgValueCandidates::new(project_candidates))]
     pub name: Option<String>,
 
     /// Update all projects (default)
@@ -198,15 +315,97 @@ pub struct UpdateArgs {
     /// Show what would be updated
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Number of projects to update concurrently (defaults to CPU count)
+    #[arg(short, long)]
+    pub jobs: Option<usize>,
+
+    /// Update the worktree of this branch instead of the default branch
+    #[arg(short, long, add = ArgValueCandidates::new(branch_candidates))]
+    pub branch: Option<String>,
+
+    /// Only include projects with this tag (repeatable, all must match)
+    #[arg(short, long = "tag", value_name = "TAG", add = ArgValueCandidates::new(tag_candidates))]
+    pub tags: Vec<String>,
+
+    /// How to integrate upstream changes (defaults to the project setting, then ff-only)
+    #[arg(short, long, value_enum)]
+    pub strategy: Option<UpdateStrategyArg>,
+
+    /// Stash local changes before updating and restore them afterwards
+    #[arg(long)]
+    pub autostash: bool,
+
+    /// Clone selected projects that are missing on disk first
+    #[arg(long)]
+    pub clone_missing: bool,
+}
+
+#[derive(clap::Args)]
+pub struct CloneArgs {
+    /// Project selector, e.g. `hosting:github tag:infra name:api-* !worktree dirty`
+    #[arg(value_name = "SELECTOR", add = ArgValueCompleter::new(selector_candidates))]
+    pub selector: Vec<String>,
+
+    /// Filter by hosting
+    #[arg(short = 'H', long, add = ArgValueCandidates::new(hosting_candidates))]
+    pub hosting: Option<String>,
+
+    /// Clone specific project
+    #[arg(short, long, add = ArgValueCandidates::new(project_candidates))]
+    pub name: Option<String>,
+
+    /// Only include projects with this tag (repeatable, all must match)
+    #[arg(short, long = "tag", value_name = "TAG", add = ArgValueCandidates::new(tag_candidates))]
+    pub tags: Vec<String>,
+
+    /// Show what would be cloned
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Number of projects to clone concurrently (defaults to CPU count)
+    #[arg(short, long)]
+    pub jobs: Option<usize>,
+}
+
+#[derive(clap::Args)]
+pub struct ExecArgs {
+    /// Project selector, e.g. `hosting:github tag:infra name:api-* !worktree dirty`
+    #[arg(value_name = "SELECTOR", add = ArgValueCompleter::new(selector_candidates))]
+    pub selector: Vec<String>,
+
+    /// Filter by hosting
+    #[arg(short = 'H', long, add = ArgValueCandidates::new(hosting_candidates))]
+    pub hosting: Option<String>,
+
+    /// Run in a specific project
+    #[arg(short, long, add = ArgValueCandidates::new(project_candidates))]
+    pub name: Option<String>,
+
+    /// Only include projects with this tag (repeatable, all must match)
+    #[arg(short, long = "tag", value_name = "TAG", add = ArgValueCandidates::new(tag_candidates))]
+    pub tags: Vec<String>,
+
+    /// Number of projects to run the command in concurrently (defaults to CPU count)
+    #[arg(short, long)]
+    pub jobs: Option<usize>,
+
+    /// Stop after the first project where the command fails
+    #[arg(long)]
+    pub fail_fast: bool,
+
+    /// Command and arguments to run, after `--`
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    pub command: Vec<String>,
 }
 
 #[derive(clap::Args)]
 pub struct SearchArgs {
-    /// Search pattern
+    /// Text to find in names and descriptions, plus optional terms like `hosting:github`
     pub pattern: String,
 
     /// Filter by hosting
-    #[arg(short = 'H', long)]
+    #[arg(short = 'H', long, add = ArgValueCandidates::new(hosting_candidates))]
     pub hosting: Option<String>,
 
     /// Search only worktrees
@@ -216,12 +415,95 @@ pub struct SearchArgs {
     /// Case-insensitive search
     #[arg(short, long)]
     pub ignore_case: bool,
+
+    /// Only include projects with this tag (repeatable, all must match)
+    #[arg(short, long = "tag", value_name = "TAG", add = ArgValueCandidates::new(tag_candidates))]
+    pub tags: Vec<String>,
 }
 
 #[derive(clap::Args)]
 pub struct NavigateArgs {
+    /// Project name or part of it; pick interactively when omitted or ambiguous
+    #[arg(add = ArgValueCandidates::new(project_candidates))]
+    pub name: Option<String>,
+
+    /// Navigate to the worktree of this branch
+    #[arg(short, long, add = ArgValueCandidates::new(branch_candidates))]
+    pub branch: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct ImportArgs {
+    /// Scan DIR for git repositories (defaults to every hosting's base path)
+    #[arg(long, value_name = "DIR", num_args = 0..=1, required = true)]
+    pub scan: Option<Option<String>>,
+
+    /// Show what would be imported without saving
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// What to do when a project with the same name already exists
+    #[arg(long, value_enum, default_value = "rename")]
+    pub on_conflict: ConflictArg,
+
+    /// How many directory levels to search below each scanned directory
+    #[arg(long, default_value_t = 4)]
+    pub max_depth: usize,
+}
+
+#[derive(clap::Args)]
+pub struct WorktreeArgs {
+    #[command(subcommand)]
+    pub command: WorktreeCommands,
+}
+
+#[derive(Subcommand)]
+pub enum WorktreeCommands {
+    /// Add a worktree for a branch next to the default branch checkout
+    Add(WorktreeAddArgs),
+
+    /// List the worktrees of a project
+    #[command(visible_alias = "ls")]
+    List(WorktreeListArgs),
+
+    /// Remove a worktree and its directory
+    #[command(visible_alias = "rm")]
+    Remove(WorktreeRemoveArgs),
+
+    /// Clean up worktrees whose directories were deleted
+    Prune(WorktreeListArgs),
+}
+
+#[derive(clap::Args)]
+pub struct WorktreeAddArgs {
     /// Project name
-    pub name: String,
+    #[arg(add = ArgValueCandidates::new(project_candidates))]
+    pub project: String,
+
+    /// Branch to check out (created if it does not exist)
+    pub branch: String,
+}
+
+#[derive(clap::Args)]
+pub struct WorktreeListArgs {
+    /// Project name
+    #[arg(add = ArgValueCandidates::new(project_candidates))]
+    pub project: String,
+}
+
+#[derive(clap::Args)]
+pub struct WorktreeRemoveArgs {
+    /// Project name
+    #[arg(add = ArgValueCandidates::new(project_candidates))]
+    pub project: String,
+
+    /// Branch of the worktree to remove
+    #[arg(add = ArgValueCandidates::new(branch_candidates))]
+    pub branch: String,
+
+    /// Remove even if the worktree has local changes or is locked
+    #[arg(short, long)]
+    pub force: bool,
 }
 
 #[derive(clap::Args)]
@@ -231,11 +513,74 @@ pub struct InitArgs {
     pub force: bool,
 }
 
+#[derive(clap::Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommands,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// List backups of the config file, newest first
+    Backups,
+
+    /// Replace the config file with a backup
+    Restore(ConfigRestoreArgs),
+}
+
+#[derive(clap::Args)]
+pub struct ConfigRestoreArgs {
+    /// Backup number from `pm config backups`, or its file name (defaults to the newest)
+    pub backup: Option<String>,
+
+    /// Skip confirmation
+    #[arg(short, long)]
+    pub force: bool,
+}
+
 #[derive(clap::Args)]
 pub struct CompletionsArgs {
     /// Shell to generate completions for
     #[arg(value_enum)]
     pub shell: Shell,
+
+    /// Print a static script that completes flags and subcommands only
+    #[arg(long = "static")]
+    pub r#static: bool,
+}
+
+#[derive(clap::Args)]
+pub struct ShellInitArgs {
+    /// Shell to generate the integration for
+    #[arg(value_enum)]
+    pub shell: Shell,
+
+    /// Name of the generated cd function
+    #[arg(long, default_value = "pcd")]
+    pub name: String,
+
+    /// Also show the current project in the shell prompt
+    #[arg(long)]
+    pub prompt: bool,
+}
+
+#[derive(clap::Args)]
+pub struct CompleteArgs {
+    /// What to list
+    #[arg(value_enum)]
+    pub kind: CompletionKind,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum CompletionKind {
+    /// Project names
+    Projects,
+    /// Hosting names
+    Hostings,
+    /// Tags used by any project
+    Tags,
+    /// Branches with a worktree in any project
+    Branches,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -245,11 +590,41 @@ pub enum OutputFormatArg {
     Yaml,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConflictArg {
+    /// Skip repositories whose name is taken
+    Skip,
+    /// Derive a different name from the owner or a numeric suffix
+    Rename,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum WorktreeLayoutArg {
+    /// Clone into <path>/<default-branch>
+    Nested,
+    /// Bare repository in <path>/.bare with the default branch as a worktree
+    Bare,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum UpdateStrategyArg {
+    /// Only fast-forward; fail if the branch has diverged
+    FfOnly,
+    /// Rebase local commits onto the upstream
+    Rebase,
+    /// Merge the upstream into the branch
+    Merge,
+    /// Fetch without touching the branch
+    FetchOnly,
+}
+
 #[derive(Clone, ValueEnum)]
+#[allow(clippy::enum_variant_names)]
 pub enum Shell {
     Bash,
     Zsh,
     Fish,
+    #[value(name = "powershell", alias = "power-shell")]
     PowerShell,
     Elvish,
 }
@@ -0,0 +1,211 @@
+use colored::Colorize;
+use std::path::Path;
+
+use crate::cli::{Cli, CloneArgs};
+use crate::config::paths::{expand_path, get_config_file_path};
+use crate::config::{Config, ConfigLock, Project};
+use crate::error::Result;
+use crate::git::worktree::add_worktree;
+use crate::git::{clone_repository, Credentials};
+use crate::models::Selector;
+use crate::parallel;
+
+/// Names of the missing projects of a selection, by what happened to them.
+#[derive(Debug, Default)]
+pub struct CloneSummary {
+    /// Cloned, or would be in a dry run.
+    pub cloned: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+impl CloneSummary {
+    /// Whether `name` was missing and has already been reported.
+    pub fn contains(&self, name: &str) -> bool {
+        [&self.cloned, &self.skipped, &self.failed]
+            .iter()
+            .any(|names| names.iter().any(|n| n == name))
+    }
+}
+
+pub fn execute(args: &CloneArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let config = Config::load_or_default(&config_path)?;
+
+    let projects = Selector::from_args(&args.selector)?
+        .hosting(args.hosting.as_deref())
+        .name(args.name.as_deref())
+        .tags(&args.tags)
+        .select(&config.projects);
+
+    let jobs = args.jobs.unwrap_or_else(parallel::default_jobs);
+    let summary = clone_missing(&config, &config_path, &projects, jobs, args.dry_run, cli)?;
+
+    if !cli.quiet && !args.dry_run {
+        if summary.cloned.is_empty() && summary.skipped.is_empty() && summary.failed.is_empty() {
+            println!("All selected projects are already cloned.");
+        } else {
+            println!();
+            println!(
+                "Summary: {} cloned, {} skipped, {} failed",
+                summary.cloned.len().to_string().green(),
+                summary.skipped.len().to_string().yellow(),
+                summary.failed.len().to_string().red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Clone every project in `projects` whose checkout does not exist yet, up to
+/// `jobs` at a time, and record the default branch of new worktree projects
+/// in the config at `config_path`.
+pub fn clone_missing(
+    config: &Config,
+    config_path: &Path,
+    projects: &[&Project],
+    jobs: usize,
+    dry_run: bool,
+    cli: &Cli,
+) -> Result<CloneSummary> {
+    let mut summary = CloneSummary::default();
+    let mut targets = Vec::new();
+
+    for project in projects {
+        let repo_path = expand_path(&project.get_repo_path())?;
+        if repo_path.exists() {
+            continue;
+        }
+
+        let url = match project.repository_url {
+            Some(ref url) => url,
+            None => {
+                if !cli.quiet {
+                    println!(
+                        "{} {} - skipped (no repository URL)",
+                        "⊗".yellow(),
+                        project.name.cyan()
+                    );
+                }
+                summary.skipped.push(project.name.clone());
+                continue;
+            }
+        };
+
+        if dry_run {
+            if !cli.quiet {
+                println!("{} Would clone {}", "ℹ".blue(), project.name.cyan());
+            }
+            summary.cloned.push(project.name.clone());
+            continue;
+        }
+
+        targets.push((*project, url.as_str(), expand_path(&project.local_path)?));
+    }
+
+    let show_progress = parallel::show_progress(jobs, cli.quiet);
+    let mut branches = Vec::new();
+
+    parallel::map(
+        &targets,
+        jobs,
+        |(project, url, local_path)| {
+            if !cli.quiet && cli.verbose {
+                println!("{} Cloning {}...", "→".blue(), project.name.cyan());
+            }
+            clone_project(config, project, url, local_path, show_progress)
+        },
+        |(project, _, _), result| match result {
+            Ok(branch) => {
+                if !cli.quiet {
+                    println!("{} Cloned {}", "✓".green().bold(), project.name.cyan());
+                }
+                if let Some(branch) = branch {
+                    if project.worktree_branch.as_ref() != Some(branch) {
+                        branches.push((project.name.clone(), branch.clone()));
+                    }
+                }
+                summary.cloned.push(project.name.clone());
+            }
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!(
+                        "{} Failed to clone {}: {}",
+                        "✗".red().bold(),
+                        project.name.cyan(),
+                        e
+                    );
+                }
+                summary.failed.push(project.name.clone());
+            }
+        },
+    );
+
+    if !branches.is_empty() {
+        // Cloning can take a while, so the config is only locked and reloaded
+        // to record the results.
+        let _lock = ConfigLock::acquire(config_path)?;
+        let mut config = Config::load_or_default(config_path)?;
+        for (name, branch) in branches {
+            if let Some(project) = config.find_project_mut(&name) {
+                project.worktree_branch = Some(branch);
+                project.update_timestamp();
+            }
+        }
+        config.save(config_path)?;
+    }
+
+    Ok(summary)
+}
+
+/// Clone one project and recreate its additional worktrees. If the clone
+/// fails and created `local_path`, it is removed again so it can be retried.
+fn clone_project(
+    config: &Config,
+    project: &Project,
+    url: &str,
+    local_path: &Path,
+    show_progress: bool,
+) -> Result<Option<String>> {
+    let created = !local_path.exists();
+
+    let layout = config.worktree_layout(project.worktree_layout, &project.hosting);
+    let credentials = Credentials::for_hosting(config, &project.hosting);
+    let cloned = clone_repository(
+        url,
+        local_path,
+        project.is_worktree,
+        layout,
+        &credentials,
+        show_progress,
+    );
+
+    let default_branch = match cloned {
+        Ok(branch) => branch,
+        Err(e) => {
+            if created && local_path.exists() {
+                let _ = std::fs::remove_dir_all(local_path);
+            }
+            return Err(e);
+        }
+    };
+
+    if let Some(ref branch) = default_branch {
+        let repo_path = local_path.join(branch);
+        for worktree in project.worktrees.iter().filter(|b| *b != branch) {
+            let worktree_path = expand_path(&project.worktree_path(worktree))?;
+            if let Err(e) = add_worktree(&repo_path, worktree, &worktree_path) {
+                eprintln!(
+                    "{} Could not recreate worktree '{}' of {}: {}",
+                    "!".yellow(),
+                    worktree,
+                    project.name.cyan(),
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(default_branch)
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+
+use clap_complete::CompletionCandidate;
+
+use crate::cli::{Cli, CompleteArgs, CompletionKind};
+use crate::config::paths::get_config_file_path;
+use crate::config::Config;
+use crate::error::Result;
+
+/// Selector keys that take a value, completed as `key:<value>`.
+const SELECTOR_KEYS: &[&str] = &["hosting:", "tag:", "name:", "branch:", "desc:", "path:"];
+
+/// Selector flags that stand on their own.
+const SELECTOR_FLAGS: &[&str] = &["worktree", "cloned", "dirty", "clean", "unpushed", "behind"];
+
+/// Print one candidate per line for shell completion scripts.
+pub fn execute(args: &CompleteArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let config = Config::load_or_default(&config_path)?;
+
+    let values = match args.kind {
+        CompletionKind::Projects => project_names(&config),
+        CompletionKind::Hostings => hosting_names(&config),
+        CompletionKind::Tags => tag_names(&config),
+        CompletionKind::Branches => branch_names(&config),
+    };
+    for value in values {
+        println!("{}", value);
+    }
+
+    Ok(())
+}
+
+/// Candidates for arguments naming a project, with the description as help.
+pub fn project_candidates() -> Vec<CompletionCandidate> {
+    let config = completion_config();
+    config
+        .projects
+        .iter()
+        .map(|project| {
+            CompletionCandidate::new(&project.name)
+                .help(project.description.clone().map(Into::into))
+        })
+        .collect()
+}
+
+/// Candidates for `--hosting`, with the base path as help.
+pub fn hosting_candidates() -> Vec<CompletionCandidate> {
+    let config = completion_config();
+    let mut hostings: Vec<_> = config.hostings.iter().collect();
+    hostings.sort_by_key(|(name, _)| name.as_str());
+    hostings
+        .into_iter()
+        .map(|(name, hosting)| {
+            CompletionCandidate::new(name).help(Some(hosting.base_path.clone().into()))
+        })
+        .collect()
+}
+
+/// Candidates for `--tag` and friends.
+pub fn tag_candidates() -> Vec<CompletionCandidate> {
+    tag_names(&completion_config())
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Candidates for `--branch`: every branch with a worktree in some project.
+pub fn branch_candidates() -> Vec<CompletionCandidate> {
+    branch_names(&completion_config())
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Candidates for selector words: `key:value` terms, flags and project names.
+pub fn selector_candidates(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let (negation, term) = match current.strip_prefix('!') {
+        Some(term) => ("!", term),
+        None => ("", current.as_ref()),
+    };
+    let config = completion_config();
+
+    let words: Vec<String> = match term.split_once(':') {
+        Some((key, _)) => {
+            let values = match key {
+                "hosting" | "host" => hosting_names(&config),
+                "tag" => tag_names(&config),
+                "name" => project_names(&config),
+                "branch" => branch_names(&config),
+                _ => Vec::new(),
+            };
+            values
+                .into_iter()
+                .map(|value| format!("{}:{}", key, value))
+                .collect()
+        }
+        None => SELECTOR_KEYS
+            .iter()
+            .chain(SELECTOR_FLAGS)
+            .map(|word| word.to_string())
+            .chain(project_names(&config))
+            .collect(),
+    };
+
+    words
+        .into_iter()
+        .filter(|word| word.starts_with(term))
+        .map(|word| CompletionCandidate::new(format!("{}{}", negation, word)))
+        .collect()
+}
+
+/// Load the config for a completion request: the `--config` value on the
+/// command line being completed, then `PM_CONFIG`. Errors yield no candidates.
+fn completion_config() -> Config {
+    let custom = config_arg().or_else(|| std::env::var("PM_CONFIG").ok());
+    get_config_file_path(custom.as_deref())
+        .and_then(|path| Config::load_or_default(&path))
+        .unwrap_or_default()
+}
+
+/// The `-c`/`--config` value among the words being completed. Completion
+/// runs as `pm -- pm <words>`, before clap has parsed anything.
+fn config_arg() -> Option<String> {
+    let args: Vec<String> = std::env::args_os()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let mut words = args.iter().skip_while(|arg| *arg != "--").skip(2);
+
+    let mut config = None;
+    while let Some(word) = words.next() {
+        if word == "-c" || word == "--config" {
+            config = words.next().cloned();
+        } else if let Some(value) = word.strip_prefix("--config=") {
+            config = Some(value.to_string());
+        }
+    }
+    config
+}
+
+fn project_names(config: &Config) -> Vec<String> {
+    config.projects.iter().map(|p| p.name.clone()).collect()
+}
+
+fn hosting_names(config: &Config) -> Vec<String> {
+    let mut names: Vec<String> = config.hostings.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+fn tag_names(config: &Config) -> Vec<String> {
+    let tags: BTreeSet<&String> = config.projects.iter().flat_map(|p| &p.tags).collect();
+    tags.into_iter().cloned().collect()
+}
+
+fn branch_names(config: &Config) -> Vec<String> {
+    let branches: BTreeSet<&String> = config
+        .projects
+        .iter()
+        .filter(|p| p.is_worktree)
+        .flat_map(|p| p.worktree_branch.iter().chain(&p.worktrees))
+        .collect();
+    branches.into_iter().cloned().collect()
+}
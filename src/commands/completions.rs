@@ -1,20 +1,36 @@
 use clap::CommandFactory;
+use clap_complete::env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Zsh};
 use clap_complete::{generate, Shell as ClapShell};
 use std::io;
 
 use crate::cli::{Cli, CompletionsArgs, Shell};
 use crate::error::Result;
 
-pub fn execute(args: CompletionsArgs) -> Result<()> {
-    let mut cmd = Cli::command();
-    let shell = match args.shell {
-        Shell::Bash => ClapShell::Bash,
-        Shell::Zsh => ClapShell::Zsh,
-        Shell::Fish => ClapShell::Fish,
-        Shell::PowerShell => ClapShell::PowerShell,
-        Shell::Elvish => ClapShell::Elvish,
-    };
+/// Environment variable the dynamic completion scripts set when calling back into pm.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+pub fn execute(args: &CompletionsArgs) -> Result<()> {
+    if args.r#static {
+        let mut cmd = Cli::command();
+        let shell = match args.shell {
+            Shell::Bash => ClapShell::Bash,
+            Shell::Zsh => ClapShell::Zsh,
+            Shell::Fish => ClapShell::Fish,
+            Shell::PowerShell => ClapShell::PowerShell,
+            Shell::Elvish => ClapShell::Elvish,
+        };
 
-    generate(shell, &mut cmd, "pm", &mut io::stdout());
+        generate(shell, &mut cmd, "pm", &mut io::stdout());
+        return Ok(());
+    }
+
+    let completer: &dyn EnvCompleter = match args.shell {
+        Shell::Bash => &Bash,
+        Shell::Zsh => &Zsh,
+        Shell::Fish => &Fish,
+        Shell::PowerShell => &Powershell,
+        Shell::Elvish => &Elvish,
+    };
+    completer.write_registration(COMPLETE_VAR, "pm", "pm", "pm", &mut io::stdout())?;
     Ok(())
 }
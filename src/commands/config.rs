@@ -0,0 +1,124 @@
+use colored::Colorize;
+use dialoguer::Confirm;
+use std::path::{Path, PathBuf};
+use tabled::settings::Style;
+use tabled::{Table, Tabled};
+
+use crate::cli::{Cli, ConfigArgs, ConfigCommands, ConfigRestoreArgs};
+use crate::config::backup::{backup_dir, list_backups};
+use crate::config::paths::get_config_file_path;
+use crate::config::{Config, ConfigLock};
+use crate::error::{ConfigError, Result};
+
+#[derive(Tabled)]
+struct BackupRow {
+    #[tabled(rename = "#")]
+    index: usize,
+    #[tabled(rename = "BACKUP")]
+    name: String,
+    #[tabled(rename = "PROJECTS")]
+    projects: String,
+}
+
+pub fn execute(args: &ConfigArgs, cli: &Cli) -> Result<()> {
+    match args.command {
+        ConfigCommands::Backups => backups(cli),
+        ConfigCommands::Restore(ref restore_args) => restore(restore_args, cli),
+    }
+}
+
+fn backups(cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let backups = list_backups(&config_path)?;
+
+    if backups.is_empty() {
+        if !cli.quiet {
+            println!("No backups found in {}", backup_dir(&config_path).display());
+        }
+        return Ok(());
+    }
+
+    let rows: Vec<BackupRow> = backups
+        .iter()
+        .enumerate()
+        .map(|(i, path)| BackupRow {
+            index: i + 1,
+            name: file_name(path),
+            projects: match Config::read(path) {
+                Ok(config) => config.projects.len().to_string(),
+                Err(_) => "invalid".red().to_string(),
+            },
+        })
+        .collect();
+
+    println!("{}", Table::new(rows).with(Style::empty()));
+    Ok(())
+}
+
+fn restore(args: &ConfigRestoreArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let _lock = ConfigLock::acquire(&config_path)?;
+
+    let backup = find_backup(&config_path, args.backup.as_deref())?;
+    let restored = Config::read(&backup)?;
+
+    let confirmed = if args.force {
+        true
+    } else {
+        Confirm::new()
+            .with_prompt(format!(
+                "Replace {} with backup '{}' ({} projects)?",
+                config_path.display(),
+                file_name(&backup),
+                restored.projects.len()
+            ))
+            .default(false)
+            .interact()?
+    };
+
+    if !confirmed {
+        if !cli.quiet {
+            println!("Cancelled.");
+        }
+        return Ok(());
+    }
+
+    // Saving backs up the current file first, so a restore can be undone.
+    restored.save(&config_path)?;
+
+    if !cli.quiet {
+        println!(
+            "{} Restored {} from {}",
+            "✓".green().bold(),
+            config_path.display().to_string().cyan(),
+            file_name(&backup).bright_black()
+        );
+    }
+
+    Ok(())
+}
+
+/// Resolve a backup given by its number in `pm config backups`, its file
+/// name or a path. Defaults to the newest backup.
+fn find_backup(config_path: &Path, backup: Option<&str>) -> Result<PathBuf> {
+    let backups = list_backups(config_path)?;
+
+    let found = match backup {
+        None => backups.into_iter().next(),
+        Some(id) => match id.parse::<usize>() {
+            Ok(n) if n >= 1 => backups.into_iter().nth(n - 1),
+            _ => backups
+                .into_iter()
+                .find(|path| file_name(path) == id)
+                .or_else(|| Some(PathBuf::from(id)).filter(|path| path.is_file())),
+        },
+    };
+
+    found.ok_or_else(|| ConfigError::BackupNotFound(backup.unwrap_or("latest").to_string()).into())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
@@ -1,14 +1,18 @@
 use colored::Colorize;
 
-use crate::cli::{Cli, CreateArgs};
-use crate::config::{Config, Project};
-use crate::config::paths::expand_path;
+use crate::cli::{Cli, CreateArgs, WorktreeLayoutArg};
+use crate::config::{Config, ConfigLock, Project, WorktreeLayout};
+use crate::config::paths::{expand_path, get_config_file_path};
 use crate::error::Result;
-use crate::git::clone_repository;
-use crate::models::compute_project_path;
+use crate::git::{clone_repository, Credentials};
+use crate::models::{compute_project_path, validate_project_name};
 
 pub fn execute(args: &CreateArgs, cli: &Cli) -> Result<()> {
-    let mut config = Config::load_or_default()?;
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let _lock = ConfigLock::acquire(&config_path)?;
+    let mut config = Config::load_or_default(&config_path)?;
+
+    validate_project_name(&args.name, &config.settings.naming)?;
 
     let hosting = if let Some(ref h) = args.hosting {
         h.clone()
@@ -26,6 +30,11 @@ pub fn execute(args: &CreateArgs, cli: &Cli) -> Result<()> {
         args.worktree,
     )?;
 
+    let project_layout = args.layout.map(|layout| match layout {
+        WorktreeLayoutArg::Nested => WorktreeLayout::Nested,
+        WorktreeLayoutArg::Bare => WorktreeLayout::Bare,
+    });
+
     let mut worktree_branch = None;
 
     if args.clone {
@@ -36,7 +45,16 @@ pub fn execute(args: &CreateArgs, cli: &Cli) -> Result<()> {
 
             let expanded_path = expand_path(&local_path)?;
 
-            worktree_branch = clone_repository(repo_url, &expanded_path, args.worktree)?;
+            let layout = config.worktree_layout(project_layout, &hosting);
+            let credentials = Credentials::for_hosting(&config, &hosting);
+            worktree_branch = clone_repository(
+                repo_url,
+                &expanded_path,
+                args.worktree,
+                layout,
+                &credentials,
+                !cli.quiet,
+            )?;
 
             if !cli.quiet {
                 println!("{} Repository cloned", "✓".green().bold());
@@ -54,9 +72,13 @@ pub fn execute(args: &CreateArgs, cli: &Cli) -> Result<()> {
     project.repository_url = args.repo.clone();
     project.is_worktree = args.worktree;
     project.worktree_branch = worktree_branch;
+    project.worktree_layout = project_layout;
+    for tag in &args.tags {
+        project.add_tag(tag);
+    }
 
     config.add_project(project)?;
-    config.save()?;
+    config.save(&config_path)?;
 
     if !cli.quiet {
         println!(
@@ -2,12 +2,14 @@ use colored::Colorize;
 use dialoguer::Confirm;
 
 use crate::cli::{Cli, DeleteArgs};
-use crate::config::Config;
-use crate::config::paths::expand_path;
+use crate::config::{Config, ConfigLock};
+use crate::config::paths::{expand_path, get_config_file_path};
 use crate::error::Result;
 
 pub fn execute(args: &DeleteArgs, cli: &Cli) -> Result<()> {
-    let mut config = Config::load_or_default()?;
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let _lock = ConfigLock::acquire(&config_path)?;
+    let mut config = Config::load_or_default(&config_path)?;
 
     let project = config.find_project(&args.name).cloned();
 
@@ -34,7 +36,7 @@ pub fn execute(args: &DeleteArgs, cli: &Cli) -> Result<()> {
     }
 
     config.remove_project(&args.name)?;
-    config.save()?;
+    config.save(&config_path)?;
 
     if !cli.quiet {
         println!(
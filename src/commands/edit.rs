@@ -1,11 +1,63 @@
 use colored::Colorize;
 
-use crate::cli::{Cli, EditArgs};
-use crate::config::Config;
+use crate::cli::{Cli, EditArgs, UpdateStrategyArg};
+use crate::commands::mv::move_and_save;
+use crate::config::{Config, ConfigLock, UpdateStrategy};
+use crate::config::paths::{expand_path, get_config_file_path};
 use crate::error::{ProjectError, Result};
+use crate::models::{compute_project_path, validate_project_name};
 
 pub fn execute(args: &EditArgs, cli: &Cli) -> Result<()> {
-    let mut config = Config::load_or_default()?;
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let _lock = ConfigLock::acquire(&config_path)?;
+    let mut config = Config::load_or_default(&config_path)?;
+
+    if let Some(ref new_name) = args.name_new {
+        validate_project_name(new_name, &config.settings.naming)?;
+        if *new_name != args.name && config.find_project(new_name).is_some() {
+            return Err(ProjectError::AlreadyExists(new_name.clone()).into());
+        }
+    }
+
+    let current = config
+        .find_project(&args.name)
+        .ok_or_else(|| ProjectError::NotFound(args.name.clone()))?;
+    let old_path = current.local_path.clone();
+    let old_repo_path = current.get_repo_path();
+
+    // A new repository URL or hosting changes where the checkout belongs.
+    let mut kept_path = false;
+    let rehome = if args.repo.is_some() || args.hosting.is_some() {
+        let hosting = if let Some(ref h) = args.hosting {
+            h.clone()
+        } else if let Some(ref url) = args.repo {
+            config.infer_hosting_from_url(url)
+        } else {
+            current.hosting.clone()
+        };
+
+        if !config.hostings.contains_key(&hosting) {
+            return Err(ProjectError::HostingNotFound(hosting).into());
+        }
+
+        // The path is derived from the URL; without one the project stays put.
+        let url = args.repo.as_deref().or(current.repository_url.as_deref());
+        let local_path = if url.is_none() && args.path.is_none() {
+            kept_path = true;
+            current.local_path.clone()
+        } else {
+            compute_project_path(
+                &config,
+                &hosting,
+                url,
+                args.path.as_deref(),
+                args.worktree || (current.is_worktree && !args.no_worktree),
+            )?
+        };
+        Some((hosting, local_path))
+    } else {
+        None
+    };
 
     let project = config
         .find_project_mut(&args.name)
@@ -38,6 +90,40 @@ pub fn execute(args: &EditArgs, cli: &Cli) -> Result<()> {
         modified = true;
     }
 
+    if let Some((hosting, local_path)) = rehome {
+        if project.hosting != hosting {
+            project.hosting = hosting;
+            modified = true;
+        }
+        if project.local_path != local_path {
+            project.local_path = local_path;
+            modified = true;
+        }
+    }
+
+    if let Some(strategy) = args.update_strategy {
+        project.update_strategy = Some(match strategy {
+            UpdateStrategyArg::FfOnly => UpdateStrategy::FfOnly,
+            UpdateStrategyArg::Rebase => UpdateStrategy::Rebase,
+            UpdateStrategyArg::Merge => UpdateStrategy::Merge,
+            UpdateStrategyArg::FetchOnly => UpdateStrategy::FetchOnly,
+        });
+        modified = true;
+    }
+
+    for tag in &args.add_tags {
+        if !project.tags.contains(tag) {
+            project.add_tag(tag);
+            modified = true;
+        }
+    }
+
+    if !args.remove_tags.is_empty() {
+        let before = project.tags.len();
+        project.tags.retain(|t| !args.remove_tags.contains(t));
+        modified |= project.tags.len() != before;
+    }
+
     if let Some(ref new_name) = args.name_new {
         project.name = new_name.clone();
         modified = true;
@@ -45,7 +131,20 @@ pub fn execute(args: &EditArgs, cli: &Cli) -> Result<()> {
 
     if modified {
         project.update_timestamp();
-        config.save()?;
+        let new_path = project.local_path.clone();
+
+        // Without --move the checkout stays where it was.
+        let repo_path = if args.move_files {
+            project.get_repo_path()
+        } else {
+            old_repo_path
+        };
+
+        if args.move_files {
+            move_and_save(&config, &config_path, &old_path, &new_path, cli)?;
+        } else {
+            config.save(&config_path)?;
+        }
 
         if !cli.quiet {
             println!(
@@ -54,9 +153,64 @@ pub fn execute(args: &EditArgs, cli: &Cli) -> Result<()> {
                 args.name.cyan()
             );
         }
+
+        if kept_path && !cli.quiet {
+            println!(
+                "{} No repository URL to derive a path from; kept {} (use --path to change it)",
+                "ℹ".blue(),
+                old_path.bright_black()
+            );
+        }
+
+        if new_path != old_path && !cli.quiet {
+            println!(
+                "  Path: {} {} {}",
+                old_path.bright_black(),
+                "→".blue(),
+                new_path.bright_black()
+            );
+            if !args.move_files && expand_path(&old_path)?.exists() {
+                println!(
+                    "{} The checkout is still at the old path; use --move to relocate it",
+                    "ℹ".blue()
+                );
+            }
+        }
+
+        if args.update_remote {
+            if let Some(ref url) = args.repo {
+                update_origin(&repo_path, url, cli)?;
+            }
+        }
     } else if !cli.quiet {
         println!("No changes made.");
     }
 
     Ok(())
 }
+
+/// Point the `origin` remote of the checkout at `url`, if it is cloned.
+fn update_origin(repo_path: &str, url: &str, cli: &Cli) -> Result<()> {
+    let path = expand_path(repo_path)?;
+    let repo = match git2::Repository::open(&path) {
+        Ok(repo) => repo,
+        Err(_) => {
+            if !cli.quiet {
+                println!(
+                    "{} No repository at {}, remote not updated",
+                    "⊗".yellow(),
+                    path.display().to_string().bright_black()
+                );
+            }
+            return Ok(());
+        }
+    };
+
+    repo.remote_set_url("origin", url)?;
+
+    if !cli.quiet {
+        println!("{} Set origin to {}", "✓".green().bold(), url.cyan());
+    }
+
+    Ok(())
+}
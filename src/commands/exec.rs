@@ -0,0 +1,135 @@
+use colored::Colorize;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use tabled::settings::Style;
+use tabled::{Table, Tabled};
+
+use crate::cli::{Cli, ExecArgs};
+use crate::config::paths::{expand_path, get_config_file_path};
+use crate::config::{Config, Project};
+use crate::error::{PmError, Result};
+use crate::models::Selector;
+use crate::parallel;
+
+#[derive(Tabled)]
+struct ExecRow {
+    #[tabled(rename = "PROJECT")]
+    name: String,
+    #[tabled(rename = "EXIT")]
+    exit: String,
+    #[tabled(rename = "RESULT")]
+    result: String,
+}
+
+pub fn execute(args: &ExecArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let config = Config::load_or_default(&config_path)?;
+
+    let projects = Selector::from_args(&args.selector)?
+        .hosting(args.hosting.as_deref())
+        .name(args.name.as_deref())
+        .tags(&args.tags)
+        .select(&config.projects);
+
+    if projects.is_empty() {
+        if !cli.quiet {
+            println!("No projects found.");
+        }
+        return Ok(());
+    }
+
+    let mut targets: Vec<(&Project, Option<PathBuf>)> = Vec::new();
+    for project in projects {
+        let repo_path = expand_path(&project.get_repo_path())?;
+        targets.push((project, Some(repo_path).filter(|p| p.exists())));
+    }
+
+    let jobs = args.jobs.unwrap_or_else(parallel::default_jobs);
+    let results = parallel::map_while(
+        &targets,
+        jobs,
+        |(_, repo_path)| {
+            repo_path.as_ref().map(|repo_path| {
+                Command::new(&args.command[0])
+                    .args(&args.command[1..])
+                    .current_dir(repo_path)
+                    .output()
+            })
+        },
+        |(project, _), result| match result {
+            Some(result) => {
+                match result {
+                    Ok(output) => print_prefixed(&project.name, output),
+                    Err(e) => eprintln!(
+                        "{} {}",
+                        format!("[{}]", project.name).cyan(),
+                        format!("failed to run '{}': {}", args.command[0], e).red()
+                    ),
+                }
+                !args.fail_fast || succeeded(result)
+            }
+            None => true,
+        },
+    );
+
+    let mut failed = 0;
+    let mut rows = Vec::new();
+
+    for ((project, _), result) in targets.iter().zip(results) {
+        let (exit, result) = match result {
+            Some(Some(Ok(output))) => {
+                let code = output
+                    .status
+                    .code()
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "signal".to_string());
+                if output.status.success() {
+                    (code, "ok".green().to_string())
+                } else {
+                    failed += 1;
+                    (code, "failed".red().to_string())
+                }
+            }
+            Some(Some(Err(e))) => {
+                failed += 1;
+                ("-".to_string(), format!("error: {}", e).red().to_string())
+            }
+            Some(None) => ("-".to_string(), "skipped (not cloned)".yellow().to_string()),
+            None => ("-".to_string(), "skipped (fail-fast)".yellow().to_string()),
+        };
+
+        rows.push(ExecRow {
+            name: project.name.clone(),
+            exit,
+            result,
+        });
+    }
+
+    if !cli.quiet {
+        println!();
+        println!("{}", Table::new(rows).with(Style::empty()));
+    }
+
+    if failed > 0 {
+        return Err(PmError::ExecFailed(failed));
+    }
+
+    Ok(())
+}
+
+fn succeeded(result: &std::io::Result<Output>) -> bool {
+    matches!(result, Ok(output) if output.status.success())
+}
+
+/// Print captured output line by line, prefixed with the project name so
+/// output from different projects stays attributable.
+fn print_prefixed(name: &str, output: &Output) {
+    let prefix = format!("[{}]", name).cyan();
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        println!("{} {}", prefix, line);
+    }
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        eprintln!("{} {}", prefix, line);
+    }
+}
@@ -0,0 +1,200 @@
+use colored::Colorize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tabled::settings::Style;
+use tabled::{Table, Tabled};
+
+use crate::cli::{Cli, ConflictArg, ImportArgs};
+use crate::config::paths::{expand_path, get_config_file_path};
+use crate::config::{Config, ConfigLock, NamingRules, Project};
+use crate::error::Result;
+use crate::git::discover::{discover_repositories, DiscoveredRepo};
+use crate::models::{sanitize_project_name, validate_project_name};
+
+#[derive(Tabled)]
+struct ImportRow {
+    #[tabled(rename = "NAME")]
+    name: String,
+    #[tabled(rename = "HOSTING")]
+    hosting: String,
+    #[tabled(rename = "WORKTREE")]
+    worktree: String,
+    #[tabled(rename = "PATH")]
+    path: String,
+    #[tabled(rename = "ACTION")]
+    action: String,
+}
+
+pub fn execute(args: &ImportArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let _lock = ConfigLock::acquire(&config_path)?;
+    let mut config = Config::load_or_default(&config_path)?;
+
+    let roots = scan_roots(&config, args)?;
+
+    let mut registered_paths = HashSet::new();
+    for project in &config.projects {
+        registered_paths.insert(expand_path(&project.local_path)?);
+    }
+    let mut taken_names: HashSet<String> = config.projects.iter().map(|p| p.name.clone()).collect();
+
+    let mut rows = Vec::new();
+    let mut imported = Vec::new();
+
+    for (root, root_hosting) in roots {
+        for repo in discover_repositories(&root, args.max_depth) {
+            let hosting = match repo.repository_url {
+                Some(ref url) => config.infer_hosting_from_url(url),
+                None => root_hosting.clone().unwrap_or_else(|| "custom".to_string()),
+            };
+
+            let mut row = ImportRow {
+                name: base_name(&repo.local_path),
+                hosting: hosting.clone(),
+                worktree: if repo.is_worktree { "yes" } else { "no" }.to_string(),
+                path: repo.local_path.display().to_string(),
+                action: String::new(),
+            };
+
+            if !registered_paths.insert(repo.local_path.clone()) {
+                row.action = "skip (already registered)".bright_black().to_string();
+                rows.push(row);
+                continue;
+            }
+
+            let naming = &config.settings.naming;
+            match unique_name(&repo.local_path, &taken_names, args.on_conflict, naming) {
+                Some(name) if validate_project_name(&name, naming).is_err() => {
+                    row.action = "skip (invalid name)".yellow().to_string();
+                }
+                Some(name) => {
+                    row.action = if name == row.name {
+                        "import".green().to_string()
+                    } else {
+                        format!("import (renamed from '{}')", row.name)
+                            .yellow()
+                            .to_string()
+                    };
+                    row.name = name.clone();
+                    taken_names.insert(name.clone());
+                    imported.push(new_project(name, hosting, repo));
+                }
+                None => {
+                    row.action = "skip (name taken)".yellow().to_string();
+                }
+            }
+
+            rows.push(row);
+        }
+    }
+
+    if rows.is_empty() {
+        if !cli.quiet {
+            println!("No repositories found.");
+        }
+        return Ok(());
+    }
+
+    if !cli.quiet {
+        println!("{}", Table::new(rows).with(Style::empty()));
+        println!();
+    }
+
+    if args.dry_run {
+        if !cli.quiet {
+            println!(
+                "{} Dry run: {} project(s) would be imported",
+                "ℹ".blue(),
+                imported.len()
+            );
+        }
+        return Ok(());
+    }
+
+    let count = imported.len();
+    for project in imported {
+        config.add_project(project)?;
+    }
+    if count > 0 {
+        config.save(&config_path)?;
+    }
+
+    if !cli.quiet {
+        println!("{} Imported {} project(s)", "✓".green().bold(), count);
+    }
+
+    Ok(())
+}
+
+/// Directories to scan, paired with the hosting they belong to when known.
+fn scan_roots(config: &Config, args: &ImportArgs) -> Result<Vec<(PathBuf, Option<String>)>> {
+    if let Some(Some(ref dir)) = args.scan {
+        return Ok(vec![(expand_path(dir)?, None)]);
+    }
+
+    let mut hostings: Vec<&String> = config.hostings.keys().collect();
+    hostings.sort();
+
+    let mut roots: Vec<(PathBuf, Option<String>)> = Vec::new();
+    for hosting in hostings {
+        let base_path = match config.get_hosting_path(hosting) {
+            Some(base_path) => expand_path(base_path)?,
+            None => continue,
+        };
+        if base_path.is_dir() && !roots.iter().any(|(root, _)| root == &base_path) {
+            roots.push((base_path, Some(hosting.clone())));
+        }
+    }
+
+    Ok(roots)
+}
+
+fn base_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Pick a name for a repository: its directory name, then `<owner>-<name>`,
+/// then a numeric suffix, with characters the naming rules don't allow
+/// replaced. With `--on-conflict skip` only the directory name is tried.
+fn unique_name(
+    path: &Path,
+    taken: &HashSet<String>,
+    on_conflict: ConflictArg,
+    naming: &NamingRules,
+) -> Option<String> {
+    let name = sanitize_project_name(&base_name(path), naming);
+    if !taken.contains(&name) {
+        return Some(name);
+    }
+
+    if on_conflict == ConflictArg::Skip {
+        return None;
+    }
+
+    let owner = path
+        .parent()
+        .map(|parent| sanitize_project_name(&base_name(parent), naming))
+        .filter(|o| !o.is_empty());
+    if let Some(owner) = owner {
+        let qualified = format!("{}-{}", owner, name);
+        if !taken.contains(&qualified) {
+            return Some(qualified);
+        }
+    }
+
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| !taken.contains(candidate))
+}
+
+fn new_project(name: String, hosting: String, repo: DiscoveredRepo) -> Project {
+    let mut project = Project::new(name, hosting, repo.local_path.to_string_lossy().to_string());
+    project.repository_url = repo.repository_url;
+    project.is_worktree = repo.is_worktree;
+    project.worktree_layout = repo.layout;
+    project.worktree_branch = repo.worktree_branch;
+    project.worktrees = repo.worktrees;
+    project
+}
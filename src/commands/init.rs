@@ -1,12 +1,14 @@
-use crate::cli::InitArgs;
-use crate::config::Config;
+use crate::cli::{Cli, InitArgs};
+use crate::config::{Config, ConfigLock};
+use crate::config::paths::get_config_file_path;
 use crate::error::Result;
 use colored::Colorize;
 
-pub fn execute(args: InitArgs) -> Result<()> {
-    Config::init(args.force)?;
+pub fn execute(args: &InitArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let _lock = ConfigLock::acquire(&config_path)?;
 
-    let config_path = crate::config::paths::get_config_file_path()?;
+    Config::init(&config_path, args.force)?;
 
     println!(
         "{} Configuration file created at {}",
@@ -1,49 +1,34 @@
-use crate::cli::{Cli, ListArgs, OutputFormatArg};
+use crate::cli::{Cli, ListArgs};
 use crate::config::{Config, OutputFormat, Project};
+use crate::config::paths::get_config_file_path;
 use crate::error::Result;
-use crate::output::{Formatter, JsonFormatter, TableFormatter, YamlFormatter};
+use crate::models::Selector;
+use crate::output::{
+    resolve_output_format, Formatter, JsonFormatter, TableFormatter, YamlFormatter,
+};
 
 pub fn execute(args: &ListArgs, cli: &Cli) -> Result<()> {
-    let config = Config::load_or_default()?;
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let config = Config::load_or_default(&config_path)?;
 
-    let mut projects: Vec<&Project> = config.projects.iter().collect();
-
-    if let Some(ref hosting) = args.hosting {
-        projects.retain(|p| &p.hosting == hosting);
-    }
-
-    if args.worktree {
-        projects.retain(|p| p.is_worktree);
-    }
-
-    if args.no_worktree {
-        projects.retain(|p| !p.is_worktree);
-    }
+    let worktree = if args.worktree {
+        Some(true)
+    } else if args.no_worktree {
+        Some(false)
+    } else {
+        None
+    };
 
-    if let Some(ref search) = args.search {
-        let search_lower = search.to_lowercase();
-        projects.retain(|p| {
-            p.name.to_lowercase().contains(&search_lower)
-                || p.description
-                    .as_ref()
-                    .map(|d| d.to_lowercase().contains(&search_lower))
-                    .unwrap_or(false)
-        });
-    }
+    let projects = Selector::from_args(&args.selector)?
+        .hosting(args.hosting.as_deref())
+        .tags(&args.tags)
+        .worktree(worktree)
+        .text(args.search.as_deref())
+        .select(&config.projects);
 
     let projects_owned: Vec<Project> = projects.into_iter().cloned().collect();
 
-    let output_format = if let Some(ref fmt) = cli.output {
-        match fmt {
-            OutputFormatArg::Table => OutputFormat::Table,
-            OutputFormatArg::Json => OutputFormat::Json,
-            OutputFormatArg::Yaml => OutputFormat::Yaml,
-        }
-    } else {
-        config.settings.default_output_format
-    };
-
-    let formatter: Box<dyn Formatter> = match output_format {
+    let formatter: Box<dyn Formatter> = match resolve_output_format(cli, &config) {
         OutputFormat::Table => Box::new(TableFormatter),
         OutputFormat::Json => Box::new(JsonFormatter),
         OutputFormat::Yaml => Box::new(YamlFormatter),
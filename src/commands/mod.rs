@@ -1,10 +1,19 @@
+pub mod clone;
+pub mod complete;
+pub mod config;
 pub mod create;
 pub mod delete;
 pub mod edit;
+pub mod exec;
+pub mod import;
 pub mod init;
 pub mod list;
+pub mod mv;
 pub mod navigate;
+pub mod prompt;
 pub mod search;
+pub mod shell_init;
 pub mod status;
 pub mod update;
+pub mod worktree;
 pub mod completions;
@@ -0,0 +1,82 @@
+use colored::Colorize;
+use std::path::Path;
+
+use crate::cli::{Cli, MvArgs};
+use crate::config::paths::{absolute_path, expand_path, get_config_file_path};
+use crate::config::{Config, ConfigLock};
+use crate::error::{ProjectError, Result};
+use crate::git::move_checkout;
+
+pub fn execute(args: &MvArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let _lock = ConfigLock::acquire(&config_path)?;
+    let mut config = Config::load_or_default(&config_path)?;
+
+    let project = config
+        .find_project_mut(&args.project)
+        .ok_or_else(|| ProjectError::NotFound(args.project.clone()))?;
+
+    let new_path = absolute_path(&args.new_path)?;
+    let old_path = std::mem::replace(&mut project.local_path, new_path.clone());
+    project.update_timestamp();
+
+    move_and_save(&config, &config_path, &old_path, &new_path, cli)?;
+
+    if !cli.quiet {
+        println!(
+            "{} Updated project '{}'",
+            "✓".green().bold(),
+            args.project.cyan()
+        );
+    }
+
+    Ok(())
+}
+
+/// Move the checkout at `old` to `new` and save the config, moving the files
+/// back if the config cannot be saved.
+pub fn move_and_save(
+    config: &Config,
+    config_path: &Path,
+    old: &str,
+    new: &str,
+    cli: &Cli,
+) -> Result<()> {
+    let from = expand_path(old)?;
+    let to = expand_path(new)?;
+
+    let moved = if from == to {
+        false
+    } else if from.exists() {
+        move_checkout(&from, &to)?;
+        true
+    } else {
+        if !cli.quiet {
+            println!(
+                "{} Nothing to move at {}",
+                "ℹ".blue(),
+                from.display().to_string().bright_black()
+            );
+        }
+        false
+    };
+
+    if let Err(e) = config.save(config_path) {
+        if moved {
+            let _ = move_checkout(&to, &from);
+        }
+        return Err(e);
+    }
+
+    if moved && !cli.quiet {
+        println!(
+            "{} Moved {} {} {}",
+            "✓".green().bold(),
+            from.display().to_string().bright_black(),
+            "→".blue(),
+            to.display().to_string().bright_black()
+        );
+    }
+
+    Ok(())
+}
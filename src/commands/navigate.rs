@@ -1,18 +1,86 @@
-use crate::cli::NavigateArgs;
-use crate::config::Config;
-use crate::config::paths::expand_path;
+use dialoguer::FuzzySelect;
+use std::io::IsTerminal;
+
+use crate::cli::{Cli, NavigateArgs};
+use crate::config::{Config, Project};
+use crate::config::paths::{expand_path, get_config_file_path};
 use crate::error::{ProjectError, Result};
+use crate::models::fuzzy::{rank_matches, suggestions};
 
-pub fn execute(args: NavigateArgs) -> Result<()> {
-    let config = Config::load_or_default()?;
+pub fn execute(args: &NavigateArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let config = Config::load_or_default(&config_path)?;
 
-    let project = config
-        .find_project(&args.name)
-        .ok_or_else(|| ProjectError::NotFound(args.name.clone()))?;
+    let project = resolve_project(&config, args.name.as_deref())?;
 
-    let expanded_path = expand_path(&project.get_repo_path())?;
+    let expanded_path = expand_path(&project.get_worktree_repo_path(args.branch.as_deref())?)?;
 
     println!("cd {}", expanded_path.display());
 
     Ok(())
 }
+
+/// Resolve a project by exact, prefix, substring or fuzzy name match,
+/// falling back to an interactive picker when the choice is ambiguous.
+fn resolve_project<'c>(config: &'c Config, name: Option<&str>) -> Result<&'c Project> {
+    let name = match name {
+        Some(name) => name,
+        None => {
+            let candidates: Vec<&Project> = config.projects.iter().collect();
+            return pick_project("", &candidates);
+        }
+    };
+
+    if let Some(project) = config.find_project(name) {
+        return Ok(project);
+    }
+
+    let matches = rank_matches(name, &config.projects);
+    match matches.len() {
+        0 => {
+            let hints = suggestions(name, &config.projects, 3);
+            if hints.is_empty() {
+                Err(ProjectError::NotFound(name.to_string()).into())
+            } else {
+                Err(ProjectError::NotFoundDidYouMean(name.to_string(), hints.join(", ")).into())
+            }
+        }
+        1 => Ok(matches[0]),
+        _ => pick_project(name, &matches),
+    }
+}
+
+fn pick_project<'c>(query: &str, candidates: &[&'c Project]) -> Result<&'c Project> {
+    if candidates.is_empty() {
+        return Err(ProjectError::NotFound(query.to_string()).into());
+    }
+
+    // The picker draws on stderr; stdout is reserved for the `cd` command
+    // that shell wrappers evaluate.
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        if query.is_empty() {
+            return Err(ProjectError::NoneSelected.into());
+        }
+        let names: Vec<&str> = candidates.iter().map(|p| p.name.as_str()).collect();
+        return Err(ProjectError::Ambiguous(query.to_string(), names.join(", ")).into());
+    }
+
+    let items: Vec<String> = candidates
+        .iter()
+        .map(|p| match p.description {
+            Some(ref description) => format!("{}  ({})", p.name, description),
+            None => p.name.clone(),
+        })
+        .collect();
+
+    let selection = FuzzySelect::new()
+        .with_prompt("Select a project")
+        .items(&items)
+        .default(0)
+        .interact_opt()?;
+
+    match selection {
+        Some(index) => Ok(candidates[index]),
+        None => Err(ProjectError::NoneSelected.into()),
+    }
+}
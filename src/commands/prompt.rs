@@ -0,0 +1,29 @@
+use crate::cli::Cli;
+use crate::config::paths::{expand_path, get_config_file_path};
+use crate::config::Config;
+use crate::error::Result;
+
+/// Print the name of the innermost project containing the current directory,
+/// or nothing when outside of all projects.
+pub fn execute(cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let config = Config::load_or_default(&config_path)?;
+    let cwd = std::env::current_dir()?;
+
+    let mut best: Option<(usize, &str)> = None;
+    for project in &config.projects {
+        let path = expand_path(&project.local_path)?;
+        if cwd.starts_with(&path) {
+            let depth = path.components().count();
+            if best.is_none_or(|(best_depth, _)| depth > best_depth) {
+                best = Some((depth, &project.name));
+            }
+        }
+    }
+
+    if let Some((_, name)) = best {
+        println!("{}", name);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::cli::{Shell, ShellInitArgs};
+use crate::error::Result;
+
+const BASH: &str = r#"# pm shell integration for bash
+__FN__() {
+    local output
+    output="$(command pm navigate "$@")" || return $?
+    builtin cd -- "${output#cd }"
+}
+
+_pm___FN___complete() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local IFS=$'\n'
+    COMPREPLY=($(compgen -W "$(command pm __complete projects 2>/dev/null)" -- "$cur"))
+}
+complete -F _pm___FN___complete __FN__
+"#;
+
+const BASH_PROMPT: &str = r#"
+__pm_prompt() {
+    local project
+    project="$(command pm __prompt 2>/dev/null)"
+    [ -n "$project" ] && printf '[%s] ' "$project"
+}
+PS1='$(__pm_prompt)'"$PS1"
+"#;
+
+const ZSH: &str = r#"# pm shell integration for zsh
+__FN__() {
+    local output
+    output="$(command pm navigate "$@")" || return $?
+    builtin cd -- "${output#cd }"
+}
+
+_pm___FN__() {
+    local -a projects
+    projects=(${(f)"$(command pm __complete projects 2>/dev/null)"})
+    compadd -a projects
+}
+(( $+functions[compdef] )) && compdef _pm___FN__ __FN__
+"#;
+
+const ZSH_PROMPT: &str = r#"
+__pm_prompt() {
+    local project
+    project="$(command pm __prompt 2>/dev/null)"
+    [[ -n "$project" ]] && print -n "[$project] "
+}
+setopt PROMPT_SUBST
+PROMPT='$(__pm_prompt)'"$PROMPT"
+"#;
+
+const FISH: &str = r#"# pm shell integration for fish
+function __FN__
+    set -l output (command pm navigate $argv); or return $status
+    builtin cd -- (string replace -r '^cd ' '' -- $output)
+end
+
+complete -c __FN__ -f -a '(command pm __complete projects 2>/dev/null)'
+"#;
+
+const FISH_PROMPT: &str = r#"
+functions -q __pm_original_fish_prompt; or functions -c fish_prompt __pm_original_fish_prompt
+function fish_prompt
+    set -l project (command pm __prompt 2>/dev/null)
+    test -n "$project"; and printf '[%s] ' $project
+    __pm_original_fish_prompt
+end
+"#;
+
+const POWERSHELL: &str = r#"# pm shell integration for PowerShell
+function __FN__ {
+    param($Name)
+    $pmArgs = @('navigate')
+    if ($Name) { $pmArgs += $Name }
+    $output = & pm @pmArgs @args
+    if ($LASTEXITCODE -ne 0) { return }
+    Set-Location -LiteralPath ($output -replace '^cd ', '')
+}
+
+Register-ArgumentCompleter -CommandName __FN__ -ParameterName Name -ScriptBlock {
+    param($commandName, $parameterName, $wordToComplete, $commandAst, $fakeBoundParameters)
+    & pm __complete projects 2>$null | Where-Object { $_ -like "$wordToComplete*" } | ForEach-Object {
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }
+}
+"#;
+
+const POWERSHELL_PROMPT: &str = r#"
+if (-not (Test-Path Function:\__pm_original_prompt)) {
+    $function:__pm_original_prompt = $function:prompt
+}
+function prompt {
+    $project = & pm __prompt 2>$null
+    if ($project) { "[$project] " + (__pm_original_prompt) } else { __pm_original_prompt }
+}
+"#;
+
+const ELVISH: &str = r#"# pm shell integration for elvish
+use str
+
+fn __FN__ {|@args|
+    var output = (pm navigate $@args)
+    cd (str:trim-prefix $output 'cd ')
+}
+
+set edit:completion:arg-completer[__FN__] = {|@words| pm __complete projects }
+"#;
+
+const ELVISH_PROMPT: &str = r#"
+var pm-original-prompt = $edit:prompt
+set edit:prompt = {
+    var project = (str:trim-space (pm __prompt | slurp))
+    if (!=s $project '') { put '['$project'] ' }
+    $pm-original-prompt
+}
+"#;
+
+pub fn execute(args: &ShellInitArgs) -> Result<()> {
+    let (script, prompt, setup) = match args.shell {
+        Shell::Bash => (BASH, BASH_PROMPT, r#"eval "$(pm shell-init bash)""#),
+        Shell::Zsh => (ZSH, ZSH_PROMPT, r#"eval "$(pm shell-init zsh)""#),
+        Shell::Fish => (FISH, FISH_PROMPT, "pm shell-init fish | source"),
+        Shell::PowerShell => (
+            POWERSHELL,
+            POWERSHELL_PROMPT,
+            "Invoke-Expression (& pm shell-init powershell | Out-String)",
+        ),
+        Shell::Elvish => (ELVISH, ELVISH_PROMPT, "eval (pm shell-init elvish | slurp)"),
+    };
+
+    print!(
+        "# Load with: {}\n{}",
+        setup,
+        script.replace("__FN__", &args.name)
+    );
+    if args.prompt {
+        print!("{}", prompt);
+    }
+
+    Ok(())
+}
@@ -1,16 +1,25 @@
 use colored::Colorize;
+use serde::Serialize;
 use tabled::{Table, Tabled};
 
 use crate::cli::{Cli, StatusArgs};
-use crate::config::Config;
-use crate::config::paths::expand_path;
+use crate::config::{Config, OutputFormat, Project};
+use crate::config::paths::{expand_path, get_config_file_path};
 use crate::error::Result;
 use crate::git::get_repository_status;
+use crate::git::status::RepoStatus;
+use crate::output::resolve_output_format;
+use crate::models::Selector;
+use crate::parallel;
 
 #[derive(Tabled)]
 struct StatusRow {
     #[tabled(rename = "Project")]
     name: String,
+    #[tabled(rename = "Branch")]
+    branch: String,
+    #[tabled(rename = "Sync")]
+    sync: String,
     #[tabled(rename = "Status")]
     status: String,
     #[tabled(rename = "Staged")]
@@ -19,78 +28,188 @@ struct StatusRow {
     modified: String,
     #[tabled(rename = "Untracked")]
     untracked: String,
+    #[tabled(rename = "Stashes")]
+    stashes: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum StatusState {
+    Clean,
+    Dirty,
+    NotCloned,
+    NotARepo,
+}
+
+/// Machine-readable status of a single project, used for JSON/YAML output.
+#[derive(Serialize)]
+struct ProjectStatus {
+    name: String,
+    path: String,
+    state: StatusState,
+    #[serde(flatten)]
+    repo: Option<RepoStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
 }
 
 pub fn execute(args: &StatusArgs, cli: &Cli) -> Result<()> {
-    let config = Config::load_or_default()?;
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let config = Config::load_or_default(&config_path)?;
+
+    let projects = Selector::from_args(&args.selector)?
+        .hosting(args.hosting.as_deref())
+        .name(args.name.as_deref())
+        .tags(&args.tags)
+        .branch(args.branch.as_deref())
+        .select(&config.projects);
+
+    let jobs = args.jobs.unwrap_or_else(parallel::default_jobs);
+    let results = parallel::map(
+        &projects,
+        jobs,
+        |project| project_status(project, args.branch.as_deref()),
+        |_, _| {},
+    );
+
+    let mut statuses = Vec::new();
+    for result in results {
+        let status = result?;
+        if matches_filters(&status, args) {
+            statuses.push(status);
+        }
+    }
+
+    match resolve_output_format(cli, &config) {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&statuses)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&statuses)?),
+        OutputFormat::Table => {
+            if statuses.is_empty() {
+                if !cli.quiet {
+                    println!("No projects found.");
+                }
+                return Ok(());
+            }
+
+            let rows: Vec<StatusRow> = statuses.iter().map(status_row).collect();
+            let table = Table::new(rows).to_string();
+            println!("{}", table);
+        }
+    }
+
+    Ok(())
+}
 
-    let mut projects = config.projects.iter().collect::<Vec<_>>();
+fn project_status(project: &Project, branch: Option<&str>) -> Result<ProjectStatus> {
+    let repo_path = expand_path(&project.get_worktree_repo_path(branch)?)?;
 
-    if let Some(ref hosting) = args.hosting {
-        projects.retain(|p| &p.hosting == hosting);
+    let mut status = ProjectStatus {
+        name: project.name.clone(),
+        path: repo_path.to_string_lossy().to_string(),
+        state: StatusState::NotCloned,
+        repo: None,
+        error: None,
+    };
+
+    if !repo_path.exists() {
+        return Ok(status);
     }
 
-    if let Some(ref name) = args.name {
-        projects.retain(|p| &p.name == name);
+    match get_repository_status(&repo_path) {
+        Ok(repo) => {
+            status.state = if repo.has_changes {
+                StatusState::Dirty
+            } else {
+                StatusState::Clean
+            };
+            status.repo = Some(repo);
+        }
+        Err(e) => {
+            status.state = StatusState::NotARepo;
+            status.error = Some(e.to_string());
+        }
     }
 
-    let mut rows = Vec::new();
+    Ok(status)
+}
 
-    for project in projects {
-        let repo_path = expand_path(&project.get_repo_path())?;
+/// Projects without git information are always shown so missing checkouts stay visible.
+fn matches_filters(status: &ProjectStatus, args: &StatusArgs) -> bool {
+    match status.repo {
+        Some(ref repo) => {
+            !((args.dirty && !repo.has_changes)
+                || (args.unpushed && repo.ahead == 0)
+                || (args.behind && repo.behind == 0))
+        }
+        None => true,
+    }
+}
 
-        if !repo_path.exists() {
-            rows.push(StatusRow {
-                name: project.name.clone(),
-                status: "Not cloned".yellow().to_string(),
+fn status_row(status: &ProjectStatus) -> StatusRow {
+    let repo = match status.repo {
+        Some(ref repo) => repo,
+        None => {
+            let label = match status.state {
+                StatusState::NotCloned => "Not cloned",
+                _ => "Not a git repo",
+            };
+            return StatusRow {
+                name: status.name.clone(),
+                branch: "-".to_string(),
+                sync: "-".to_string(),
+                status: label.yellow().to_string(),
                 staged: "-".to_string(),
                 modified: "-".to_string(),
                 untracked: "-".to_string(),
-            });
-            continue;
+                stashes: "-".to_string(),
+            };
         }
+    };
 
-        match get_repository_status(&repo_path) {
-            Ok(status) => {
-                let status_str = if status.has_changes {
-                    "Dirty".red().to_string()
-                } else {
-                    "Clean".green().to_string()
-                };
+    let status_str = if repo.has_changes {
+        "Dirty".red().to_string()
+    } else {
+        "Clean".green().to_string()
+    };
 
-                if args.dirty && !status.has_changes {
-                    continue;
-                }
+    StatusRow {
+        name: status.name.clone(),
+        branch: branch_label(repo),
+        sync: sync_label(repo),
+        status: status_str,
+        staged: repo.staged.to_string(),
+        modified: repo.modified.to_string(),
+        untracked: repo.untracked.to_string(),
+        stashes: repo.stash_count.to_string(),
+    }
+}
 
-                rows.push(StatusRow {
-                    name: project.name.clone(),
-                    status: status_str,
-                    staged: status.staged.to_string(),
-                    modified: status.modified.to_string(),
-                    untracked: status.untracked.to_string(),
-                });
-            }
-            Err(_) => {
-                rows.push(StatusRow {
-                    name: project.name.clone(),
-                    status: "Not a git repo".yellow().to_string(),
-                    staged: "-".to_string(),
-                    modified: "-".to_string(),
-                    untracked: "-".to_string(),
-                });
-            }
-        }
+fn branch_label(status: &RepoStatus) -> String {
+    let branch = if status.detached {
+        "(detached)".yellow().to_string()
+    } else {
+        status.branch.clone().unwrap_or_else(|| "-".to_string())
+    };
+
+    match status.operation {
+        Some(operation) => format!("{} ({})", branch, operation.to_string().red()),
+        None => branch,
     }
+}
 
-    if rows.is_empty() {
-        if !cli.quiet {
-            println!("No projects found.");
-        }
-        return Ok(());
+fn sync_label(status: &RepoStatus) -> String {
+    if status.detached {
+        return "-".to_string();
     }
 
-    let table = Table::new(rows).to_string();
-    println!("{}", table);
+    if status.upstream.is_none() {
+        return "no upstream".bright_black().to_string();
+    }
 
-    Ok(())
+    match (status.ahead, status.behind) {
+        (0, 0) => "up to date".green().to_string(),
+        (ahead, 0) => format!("{} ahead", ahead).yellow().to_string(),
+        (0, behind) => format!("{} behind", behind).yellow().to_string(),
+        (ahead, behind) => format!("{} ahead, {} behind", ahead, behind).red().to_string(),
+    }
 }
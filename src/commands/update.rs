@@ -1,23 +1,25 @@
 use colored::Colorize;
 
-use crate::cli::{Cli, UpdateArgs};
-use crate::config::Config;
-use crate::config::paths::expand_path;
-use crate::error::Result;
-use crate::git::update_repository;
+use crate::cli::{Cli, UpdateArgs, UpdateStrategyArg};
+use crate::commands::clone::{clone_missing, CloneSummary};
+use crate::config::{Config, Project, UpdateStrategy};
+use crate::config::paths::{expand_path, get_config_file_path};
+use crate::error::{PmError, Result};
+use crate::git::update::UpdateOptions;
+use crate::git::{update_repository, Credentials};
+use crate::models::Selector;
+use crate::parallel;
 
 pub fn execute(args: &UpdateArgs, cli: &Cli) -> Result<()> {
-    let config = Config::load_or_default()?;
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let config = Config::load_or_default(&config_path)?;
 
-    let mut projects = config.projects.iter().collect::<Vec<_>>();
-
-    if let Some(ref hosting) = args.hosting {
-        projects.retain(|p| &p.hosting == hosting);
-    }
-
-    if let Some(ref name) = args.name {
-        projects.retain(|p| &p.name == name);
-    }
+    let projects = Selector::from_args(&args.selector)?
+        .hosting(args.hosting.as_deref())
+        .name(args.name.as_deref())
+        .tags(&args.tags)
+        .branch(args.branch.as_deref())
+        .select(&config.projects);
 
     if projects.is_empty() {
         if !cli.quiet {
@@ -26,71 +28,165 @@ pub fn execute(args: &UpdateArgs, cli: &Cli) -> Result<()> {
         return Ok(());
     }
 
-    let mut success_count = 0;
-    let mut error_count = 0;
-
-    for project in projects {
-        let repo_path = expand_path(&project.get_repo_path())?;
-
-        if !repo_path.exists() {
-            if !cli.quiet {
-                println!(
-                    "{} {} - skipped (not cloned)",
-                    "⊗".yellow(),
-                    project.name.cyan()
-                );
-            }
-            continue;
-        }
+    let jobs = args.jobs.unwrap_or_else(parallel::default_jobs);
 
-        if args.dry_run {
-            if !cli.quiet {
-                println!(
-                    "{} Would update {}",
-                    "ℹ".blue(),
-                    project.name.cyan()
-                );
-            }
-            continue;
-        }
+    let cloned = if args.clone_missing {
+        clone_missing(&config, &config_path, &projects, jobs, args.dry_run, cli)?
+    } else {
+        CloneSummary::default()
+    };
 
-        if !cli.quiet && cli.verbose {
-            println!("{} Updating {}...", "→".blue(), project.name.cyan());
-        }
+    let mut targets = Vec::new();
+    let mut skipped_count = cloned.skipped.len();
+
+    // Freshly cloned projects are already up to date, and the clone step has
+    // reported the ones it could not clone.
+    for project in projects.into_iter().filter(|p| !cloned.contains(&p.name)) {
+        let mut checkouts = Vec::new();
+
+        for (branch, path) in checkouts_to_update(project, args.branch.as_deref())? {
+            let label = checkout_label(project, branch.as_deref());
+            let repo_path = expand_path(&path)?;
 
-        match update_repository(&repo_path) {
-            Ok(_) => {
+            if !repo_path.exists() {
                 if !cli.quiet {
-                    println!(
-                        "{} Updated {}",
-                        "✓".green().bold(),
-                        project.name.cyan()
-                    );
+                    println!("{} {} - skipped (not cloned)", "⊗".yellow(), label.cyan());
                 }
-                success_count += 1;
+                skipped_count += 1;
+                continue;
             }
-            Err(e) => {
+
+            if args.dry_run {
                 if !cli.quiet {
-                    eprintln!(
-                        "{} Failed to update {}: {}",
-                        "✗".red().bold(),
-                        project.name.cyan(),
-                        e
-                    );
+                    println!("{} Would update {}", "ℹ".blue(), label.cyan());
                 }
-                error_count += 1;
+                continue;
             }
+
+            checkouts.push((label, repo_path));
+        }
+
+        if !checkouts.is_empty() {
+            targets.push((project, checkouts));
         }
     }
 
+    let show_progress = parallel::show_progress(jobs, cli.quiet);
+
+    let strategy = args.strategy.map(|strategy| match strategy {
+        UpdateStrategyArg::FfOnly => UpdateStrategy::FfOnly,
+        UpdateStrategyArg::Rebase => UpdateStrategy::Rebase,
+        UpdateStrategyArg::Merge => UpdateStrategy::Merge,
+        UpdateStrategyArg::FetchOnly => UpdateStrategy::FetchOnly,
+    });
+
+    let mut success_count = 0;
+    let mut error_count = cloned.failed.len();
+
+    // Worktrees of one project share a repository, so they are updated one
+    // after another by the same worker.
+    parallel::map(
+        &targets,
+        jobs,
+        |(project, checkouts)| {
+            let options = UpdateOptions {
+                strategy: strategy.or(project.update_strategy).unwrap_or_default(),
+                autostash: args.autostash,
+                show_progress,
+            };
+            let credentials = Credentials::for_hosting(&config, &project.hosting);
+
+            checkouts
+                .iter()
+                .map(|(label, repo_path)| {
+                    if !cli.quiet && cli.verbose {
+                        println!("{} Updating {}...", "→".blue(), label.cyan());
+                    }
+                    update_repository(repo_path, &options, &credentials)
+                })
+                .collect::<Vec<_>>()
+        },
+        |(_, checkouts), results| {
+            for ((label, _), result) in checkouts.iter().zip(results) {
+                match result {
+                    Ok(outcome) if outcome.is_skipped() => {
+                        if !cli.quiet {
+                            println!("{} {} - {}", "⊗".yellow(), label.cyan(), outcome);
+                        }
+                        skipped_count += 1;
+                    }
+                    Ok(outcome) => {
+                        if !cli.quiet {
+                            println!(
+                                "{} Updated {} {}",
+                                "✓".green().bold(),
+                                label.cyan(),
+                                format!("({})", outcome).bright_black()
+                            );
+                        }
+                        success_count += 1;
+                    }
+                    Err(e) => {
+                        if !cli.quiet {
+                            eprintln!(
+                                "{} Failed to update {}: {}",
+                                "✗".red().bold(),
+                                label.cyan(),
+                                e
+                            );
+                        }
+                        error_count += 1;
+                    }
+                }
+            }
+        },
+    );
+
     if !cli.quiet && !args.dry_run {
         println!();
+        if args.clone_missing {
+            print!("Summary: {} cloned, ", cloned.cloned.len().to_string().green());
+        } else {
+            print!("Summary: ");
+        }
         println!(
-            "Summary: {} succeeded, {} failed",
+            "{} succeeded, {} skipped, {} failed",
             success_count.to_string().green(),
+            skipped_count.to_string().yellow(),
             error_count.to_string().red()
         );
     }
 
+    if error_count > 0 {
+        return Err(PmError::UpdateFailed(error_count));
+    }
+
     Ok(())
 }
+
+/// The checkouts `pm update` touches for `project`: the worktree of `branch`
+/// when given, every worktree of a worktree project, or the single clone.
+fn checkouts_to_update(
+    project: &Project,
+    branch: Option<&str>,
+) -> Result<Vec<(Option<String>, String)>> {
+    if let Some(branch) = branch {
+        let path = project.get_worktree_repo_path(Some(branch))?;
+        return Ok(vec![(Some(branch.to_string()), path)]);
+    }
+
+    match project.worktree_branch {
+        Some(ref default_branch) if project.is_worktree => Ok(std::iter::once(default_branch)
+            .chain(&project.worktrees)
+            .map(|branch| (Some(branch.clone()), project.worktree_path(branch)))
+            .collect()),
+        _ => Ok(vec![(None, project.get_repo_path())]),
+    }
+}
+
+fn checkout_label(project: &Project, branch: Option<&str>) -> String {
+    match branch {
+        Some(branch) if project.is_worktree => format!("{} [{}]", project.name, branch),
+        _ => project.name.clone(),
+    }
+}
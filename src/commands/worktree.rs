@@ -0,0 +1,193 @@
+use colored::Colorize;
+use tabled::settings::Style;
+use tabled::{Table, Tabled};
+
+use crate::cli::{
+    Cli, WorktreeAddArgs, WorktreeArgs, WorktreeCommands, WorktreeListArgs, WorktreeRemoveArgs,
+};
+use crate::config::paths::{expand_path, get_config_file_path};
+use crate::config::{Config, ConfigLock, Project};
+use crate::error::{ProjectError, Result};
+use crate::git::worktree::{add_worktree, list_worktrees, prune_worktrees, remove_worktree};
+
+#[derive(Tabled)]
+struct WorktreeRow {
+    #[tabled(rename = "BRANCH")]
+    branch: String,
+    #[tabled(rename = "PATH")]
+    path: String,
+    #[tabled(rename = "STATE")]
+    state: String,
+}
+
+pub fn execute(args: &WorktreeArgs, cli: &Cli) -> Result<()> {
+    match args.command {
+        WorktreeCommands::Add(ref args) => add(args, cli),
+        WorktreeCommands::List(ref args) => list(args, cli),
+        WorktreeCommands::Remove(ref args) => remove(args, cli),
+        WorktreeCommands::Prune(ref args) => prune(args, cli),
+    }
+}
+
+/// Find a worktree project, failing for regular projects whose `local_path`
+/// is the repository itself and therefore has no room for sibling worktrees.
+fn find_worktree_project<'a>(config: &'a mut Config, name: &str) -> Result<&'a mut Project> {
+    let project = config
+        .find_project_mut(name)
+        .ok_or_else(|| ProjectError::NotFound(name.to_string()))?;
+
+    if !project.is_worktree {
+        return Err(ProjectError::NotWorktree(name.to_string()).into());
+    }
+
+    Ok(project)
+}
+
+fn add(args: &WorktreeAddArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let _lock = ConfigLock::acquire(&config_path)?;
+    let mut config = Config::load_or_default(&config_path)?;
+
+    let project = find_worktree_project(&mut config, &args.project)?;
+
+    if project.has_worktree(&args.branch) {
+        return Err(
+            ProjectError::WorktreeAlreadyExists(args.project.clone(), args.branch.clone()).into(),
+        );
+    }
+
+    let repo_path = expand_path(&project.get_repo_path())?;
+    let worktree_path = expand_path(&project.worktree_path(&args.branch))?;
+
+    add_worktree(&repo_path, &args.branch, &worktree_path)?;
+
+    project.worktrees.push(args.branch.clone());
+    project.update_timestamp();
+    config.save(&config_path)?;
+
+    if !cli.quiet {
+        println!(
+            "{} Added worktree '{}' to '{}'",
+            "✓".green().bold(),
+            args.branch.cyan(),
+            args.project.cyan()
+        );
+        println!(
+            "  Path: {}",
+            worktree_path.display().to_string().bright_black()
+        );
+    }
+
+    Ok(())
+}
+
+fn list(args: &WorktreeListArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let mut config = Config::load_or_default(&config_path)?;
+
+    let project = find_worktree_project(&mut config, &args.project)?;
+    let repo_path = expand_path(&project.get_repo_path())?;
+
+    let rows: Vec<WorktreeRow> = list_worktrees(&repo_path)?
+        .into_iter()
+        .map(|wt| {
+            let state = if wt.is_main {
+                "main"
+            } else if wt.prunable {
+                "prunable"
+            } else if wt.locked {
+                "locked"
+            } else {
+                "linked"
+            };
+            WorktreeRow {
+                branch: wt.branch.unwrap_or_else(|| "(detached)".to_string()),
+                path: wt.path.display().to_string(),
+                state: state.to_string(),
+            }
+        })
+        .collect();
+
+    let table = Table::new(rows).with(Style::empty()).to_string();
+    println!("{}", table);
+
+    Ok(())
+}
+
+fn remove(args: &WorktreeRemoveArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let _lock = ConfigLock::acquire(&config_path)?;
+    let mut config = Config::load_or_default(&config_path)?;
+
+    let project = find_worktree_project(&mut config, &args.project)?;
+
+    if !project.worktrees.contains(&args.branch) {
+        return Err(
+            ProjectError::WorktreeNotFound(args.project.clone(), args.branch.clone()).into(),
+        );
+    }
+
+    let repo_path = expand_path(&project.get_repo_path())?;
+    remove_worktree(&repo_path, &args.branch, args.force)?;
+
+    project.worktrees.retain(|b| b != &args.branch);
+    project.update_timestamp();
+    config.save(&config_path)?;
+
+    if !cli.quiet {
+        println!(
+            "{} Removed worktree '{}' from '{}'",
+            "✓".green().bold(),
+            args.branch.cyan(),
+            args.project.cyan()
+        );
+    }
+
+    Ok(())
+}
+
+fn prune(args: &WorktreeListArgs, cli: &Cli) -> Result<()> {
+    let config_path = get_config_file_path(cli.config.as_deref())?;
+    let _lock = ConfigLock::acquire(&config_path)?;
+    let mut config = Config::load_or_default(&config_path)?;
+
+    let project = find_worktree_project(&mut config, &args.project)?;
+    let repo_path = expand_path(&project.get_repo_path())?;
+
+    let pruned = prune_worktrees(&repo_path)?;
+
+    let mut missing = Vec::new();
+    for branch in &project.worktrees {
+        if !expand_path(&project.worktree_path(branch))?.exists() {
+            missing.push(branch.clone());
+        }
+    }
+
+    if !missing.is_empty() {
+        project.worktrees.retain(|b| !missing.contains(b));
+        project.update_timestamp();
+        config.save(&config_path)?;
+    }
+
+    if !cli.quiet {
+        if pruned.is_empty() && missing.is_empty() {
+            println!("Nothing to prune.");
+        }
+        for name in &pruned {
+            println!(
+                "{} Pruned worktree metadata '{}'",
+                "✓".green().bold(),
+                name.cyan()
+            );
+        }
+        for branch in &missing {
+            println!(
+                "{} Unregistered missing worktree '{}'",
+                "✓".green().bold(),
+                branch.cyan()
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{ConfigError, Result};
+
+/// Directory holding the backups of `config_path`.
+pub fn backup_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("backups")
+}
+
+/// Copy the current config file into the backup directory as
+/// `<file name>.<timestamp>` and drop the oldest backups beyond `keep`.
+/// Does nothing if `keep` is 0 or there is no file yet.
+pub fn create_backup(config_path: &Path, keep: usize) -> Result<Option<PathBuf>> {
+    if keep == 0 || !config_path.exists() {
+        return Ok(None);
+    }
+
+    let dir = backup_dir(config_path);
+    fs::create_dir_all(&dir)
+        .map_err(|e| ConfigError::DirectoryCreation(format!("{}: {}", dir.display(), e)))?;
+
+    let timestamp = Local::now().format("%Y%m%d-%H%M%S%.3f");
+    let backup = dir.join(format!("{}.{}", file_name(config_path), timestamp));
+    fs::copy(config_path, &backup)?;
+
+    for old in list_backups(config_path)?.into_iter().skip(keep) {
+        fs::remove_file(old)?;
+    }
+
+    Ok(Some(backup))
+}
+
+/// Copy the config as it was before an upgrade from `version` to
+/// `<file name>.v<version>` next to it. The copy lives outside the backup
+/// directory, so rotation never removes it, and an existing copy is kept.
+pub fn save_pre_upgrade_copy(config_path: &Path, version: &str) -> Result<PathBuf> {
+    let copy = config_path.with_file_name(format!("{}.v{}", file_name(config_path), version));
+    if !copy.exists() {
+        fs::copy(config_path, &copy)?;
+    }
+    Ok(copy)
+}
+
+/// Backups of `config_path`, newest first.
+pub fn list_backups(config_path: &Path) -> Result<Vec<PathBuf>> {
+    let dir = backup_dir(config_path);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let prefix = format!("{}.", file_name(config_path));
+    let mut backups: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(&prefix))
+        })
+        .collect();
+
+    // Timestamps sort lexically.
+    backups.sort();
+    backups.reverse();
+    Ok(backups)
+}
+
+fn file_name(config_path: &Path) -> String {
+    config_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "projects.toml".to_string())
+}
@@ -1,59 +1,289 @@
+use colored::Colorize;
 use std::fs;
+use std::io::Write;
+use std::path::Path;
 
-use crate::config::paths::{get_config_dir, get_config_file_path};
+use crate::config::backup::{create_backup, save_pre_upgrade_copy};
+use crate::config::lock::ConfigLock;
+use crate::config::paths::expand_path;
 use crate::config::schema::Config;
-use crate::error::{ConfigError, Result};
+use crate::error::{ConfigError, PmError, Result};
+use crate::git::clone::BARE_DIR;
+
+/// Schema version written by this build.
+pub const CURRENT_VERSION: &str = "1.1";
+
+/// One upgrade step from `from` to the next schema version `to`.
+struct Migration {
+    from: &'static str,
+    to: &'static str,
+    apply: fn(&mut Config),
+}
+
+/// Upgrade steps in order. Fields added with serde defaults need no step;
+/// add one when existing data has to be filled in or rewritten.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: "1.0",
+    to: "1.1",
+    apply: backfill_worktree_branch,
+}];
 
 impl Config {
-    pub fn load() -> Result<Self> {
-        let config_path = get_config_file_path()?;
+    /// Load a config file, upgrading it to the current schema version. The
+    /// upgraded file is written back (keeping a copy of the original) when no
+    /// other command holds the config lock.
+    pub fn load(config_path: &Path) -> Result<Self> {
+        let content = read_config_file(config_path)?;
+        check_version(&content)?;
+        let mut config = parse_config(config_path, &content)?;
+
+        if let Some(previous) = config.migrate()? {
+            config.persist_upgrade(config_path, &content, &previous)?;
+        }
+
+        Ok(config)
+    }
+
+    /// Like `load`, but never writes the file back. Used for backups.
+    pub fn read(config_path: &Path) -> Result<Self> {
+        let content = read_config_file(config_path)?;
+        check_version(&content)?;
+        let mut config = parse_config(config_path, &content)?;
+        config.migrate()?;
+        Ok(config)
+    }
 
-        if !config_path.exists() {
-            return Err(ConfigError::NotFound(
-                config_path.to_string_lossy().to_string(),
+    /// Apply the upgrade steps between the stored and the current version.
+    /// Returns the stored version if anything changed.
+    fn migrate(&mut self) -> Result<Option<String>> {
+        let stored = parse_version(&self.version)?;
+        let current = parse_version(CURRENT_VERSION)?;
+
+        if stored > current {
+            return Err(ConfigError::UnsupportedVersion(
+                self.version.clone(),
+                CURRENT_VERSION.to_string(),
             )
             .into());
         }
+        if stored == current {
+            return Ok(None);
+        }
 
-        let content = fs::read_to_string(&config_path)?;
-        let config: Config = toml::from_str(&content)?;
-        Ok(config)
+        let previous = self.version.clone();
+        let mut version = stored;
+        while version < current {
+            let migration = MIGRATIONS
+                .iter()
+                .find(|m| parse_version(m.from).ok() == Some(version))
+                .ok_or_else(|| {
+                    ConfigError::Invalid(format!("no upgrade path from version {}", self.version))
+                })?;
+
+            (migration.apply)(self);
+            self.version = migration.to.to_string();
+            version = parse_version(migration.to)?;
+        }
+
+        Ok(Some(previous))
     }
 
-    pub fn load_or_default() -> Result<Self> {
-        match Self::load() {
-            Ok(config) => Ok(config),
-            Err(_) => Ok(Config::default()),
+    /// Write an upgraded config back to disk. Commands that modify the config
+    /// already hold the lock and save the upgraded config themselves, so this
+    /// only writes when the lock is free and the file is still unchanged.
+    fn persist_upgrade(&self, config_path: &Path, original: &str, previous: &str) -> Result<()> {
+        let _lock = match ConfigLock::try_acquire(config_path)? {
+            Some(lock) => lock,
+            None => return Ok(()),
+        };
+        if fs::read_to_string(config_path)? != original {
+            return Ok(());
         }
+
+        // Loading must not rotate away the user's backups, so the original is
+        // kept in its own file instead.
+        let copy = save_pre_upgrade_copy(config_path, previous)?;
+        write_atomic(config_path, toml::to_string_pretty(self)?.as_bytes())?;
+
+        eprintln!(
+            "{} Upgraded {} from version {} to {} (original: {})",
+            "ℹ".blue(),
+            config_path.display(),
+            previous,
+            self.version,
+            copy.display()
+        );
+        Ok(())
     }
 
-    pub fn save(&self) -> Result<()> {
-        let config_dir = get_config_dir()?;
+    /// Load the config, or start from the defaults if the file doesn't exist
+    /// yet. Any other error, such as a file that fails to parse, is returned
+    /// so that a later `save` can't replace it.
+    pub fn load_or_default(config_path: &Path) -> Result<Self> {
+        match Self::load(config_path) {
+            Ok(config) => Ok(config),
+            Err(PmError::Config(ConfigError::NotFound(_))) => Ok(Config::default()),
+            Err(e) => Err(e),
+        }
+    }
 
-        if !config_dir.exists() {
-            fs::create_dir_all(&config_dir).map_err(|e| {
-                ConfigError::DirectoryCreation(format!("{}: {}", config_dir.display(), e))
-            })?;
+    pub fn save(&self, config_path: &Path) -> Result<()> {
+        if let Some(config_dir) = config_path.parent() {
+            if !config_dir.as_os_str().is_empty() && !config_dir.exists() {
+                fs::create_dir_all(config_dir).map_err(|e| {
+                    ConfigError::DirectoryCreation(format!("{}: {}", config_dir.display(), e))
+                })?;
+            }
         }
 
-        let config_path = get_config_file_path()?;
         let content = toml::to_string_pretty(self)?;
-        fs::write(&config_path, content)?;
-        Ok(())
+        create_backup(config_path, self.settings.max_backups)?;
+        write_atomic(config_path, content.as_bytes())
     }
 
-    pub fn init(force: bool) -> Result<()> {
-        let config_path = get_config_file_path()?;
-
+    pub fn init(config_path: &Path, force: bool) -> Result<()> {
         if config_path.exists() && !force {
-            return Err(ConfigError::Invalid(
-                "Config file already exists. Use --force to overwrite.".to_string(),
-            )
-            .into());
+            return Err(ConfigError::AlreadyExists(config_path.display().to_string()).into());
         }
 
         let config = Config::default();
-        config.save()?;
+        config.save(config_path)?;
         Ok(())
     }
 }
+
+/// Write `content` to a temporary file next to `path` and rename it into
+/// place, so readers never see a partially written config. A symlinked
+/// `path` is written through, and the file keeps its permissions.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let permissions = fs::metadata(&path).ok().map(|m| m.permissions());
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".tmp.{}", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            if let Some(permissions) = permissions {
+                file.set_permissions(permissions)?;
+            }
+            file.write_all(content)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp_path, &path));
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+fn read_config_file(config_path: &Path) -> Result<String> {
+    if !config_path.exists() {
+        return Err(ConfigError::NotFound(config_path.to_string_lossy().to_string()).into());
+    }
+    Ok(fs::read_to_string(config_path)?)
+}
+
+/// Parse config file content, reporting syntax and schema errors with the
+/// file, line and column they occur at.
+fn parse_config(config_path: &Path, content: &str) -> Result<Config> {
+    toml::from_str(content).map_err(|e| {
+        let location = match e.span() {
+            Some(span) => {
+                let before = &content[..span.start.min(content.len())];
+                let line = before.matches('\n').count() + 1;
+                let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+                format!("{}:{}:{}", config_path.display(), line, column)
+            }
+            None => config_path.display().to_string(),
+        };
+        ConfigError::Invalid(format!("{}: {}", location, e.message())).into()
+    })
+}
+
+/// Refuse files written by a newer pm before parsing them, since their
+/// schema may not parse with this build at all.
+fn check_version(content: &str) -> Result<()> {
+    let table: toml::Table = match content.parse() {
+        Ok(table) => table,
+        // Syntax errors are reported with their location by `parse_config`.
+        Err(_) => return Ok(()),
+    };
+    let version = match table.get("version").and_then(|v| v.as_str()) {
+        Some(version) => version,
+        None => return Ok(()),
+    };
+
+    if parse_version(version)? > parse_version(CURRENT_VERSION)? {
+        return Err(ConfigError::UnsupportedVersion(
+            version.to_string(),
+            CURRENT_VERSION.to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn parse_version(version: &str) -> Result<(u32, u32)> {
+    let invalid = || ConfigError::Invalid(format!("unknown config version '{}'", version));
+    let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+    let major = major.trim().parse().map_err(|_| invalid())?;
+    let minor = minor.trim().parse().map_err(|_| invalid())?;
+    Ok((major, minor))
+}
+
+/// 1.0 → 1.1: record the default branch of worktree projects, which older
+/// versions found by scanning `local_path` for a checkout on every use.
+/// Projects whose main checkout can't be told apart are left unset.
+fn backfill_worktree_branch(config: &mut Config) {
+    for project in &mut config.projects {
+        if !project.is_worktree || project.worktree_branch.is_some() {
+            continue;
+        }
+
+        let local_path = match expand_path(&project.local_path) {
+            Ok(path) => path,
+            Err(_) => continue,
+        };
+        if !local_path.is_dir() {
+            continue;
+        }
+
+        project.worktree_branch = main_checkout(&local_path);
+        if project.worktree_branch.is_none() {
+            eprintln!(
+                "{} Could not find the main checkout of worktree project {}; set its `worktree_branch` in the config",
+                "!".yellow(),
+                project.name.cyan()
+            );
+        }
+    }
+}
+
+/// The branch of the main checkout in a worktree project's directory: the
+/// HEAD of `.bare` for the bare layout, otherwise the only subdirectory with
+/// a `.git` directory (linked worktrees have a `.git` file instead).
+fn main_checkout(local_path: &Path) -> Option<String> {
+    if let Ok(head) = fs::read_to_string(local_path.join(BARE_DIR).join("HEAD")) {
+        return head
+            .trim()
+            .strip_prefix("ref: refs/heads/")
+            .map(|branch| branch.to_string());
+    }
+
+    let mut checkouts = fs::read_dir(local_path)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().join(".git").is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.'));
+
+    match (checkouts.next(), checkouts.next()) {
+        (Some(branch), None) => Some(branch),
+        _ => None,
+    }
+}
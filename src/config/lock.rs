@@ -0,0 +1,55 @@
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+
+use crate::error::{ConfigError, Result};
+
+/// Advisory lock on a config file, held from load to save by commands that
+/// modify it so concurrent `pm` invocations don't overwrite each other.
+///
+/// The lock lives in a `<config>.lock` file next to the config, because the
+/// config itself is replaced on every save. It is released on drop.
+pub struct ConfigLock {
+    _file: File,
+}
+
+impl ConfigLock {
+    /// Block until the lock for `config_path` is available.
+    pub fn acquire(config_path: &Path) -> Result<Self> {
+        let file = open_lock_file(config_path)?;
+        file.lock()?;
+        Ok(ConfigLock { _file: file })
+    }
+
+    /// Take the lock if nobody holds it, including this process.
+    pub fn try_acquire(config_path: &Path) -> Result<Option<Self>> {
+        let file = open_lock_file(config_path)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(ConfigLock { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e.into()),
+        }
+    }
+}
+
+fn open_lock_file(config_path: &Path) -> Result<File> {
+    let lock_path = lock_path(config_path);
+    if let Some(dir) = lock_path.parent() {
+        if !dir.as_os_str().is_empty() && !dir.exists() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| ConfigError::DirectoryCreation(format!("{}: {}", dir.display(), e)))?;
+        }
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+    Ok(file)
+}
+
+fn lock_path(config_path: &Path) -> PathBuf {
+    let mut name = config_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    config_path.with_file_name(name)
+}
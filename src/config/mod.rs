@@ -1,5 +1,8 @@
+pub mod backup;
 pub mod loader;
+pub mod lock;
 pub mod paths;
 pub mod schema;
 
-pub use schema::{Config, OutputFormat, Project};
+pub use lock::ConfigLock;
+pub use schema::{Config, NamingRules, OutputFormat, Project, UpdateStrategy, WorktreeLayout};
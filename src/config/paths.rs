@@ -13,11 +13,27 @@ pub fn get_config_dir() -> Result<PathBuf> {
     Ok(base_dir.join("pm"))
 }
 
-pub fn get_config_file_path() -> Result<PathBuf> {
-    Ok(get_config_dir()?.join("projects.toml"))
+/// Resolve the config file to operate on.
+/// A custom path (from `--config` or `PM_CONFIG`) takes precedence over the
+/// default `projects.toml` in the config directory.
+pub fn get_config_file_path(custom_path: Option<&str>) -> Result<PathBuf> {
+    match custom_path {
+        Some(path) if !path.is_empty() => expand_path(path),
+        _ => Ok(get_config_dir()?.join("projects.toml")),
+    }
 }
 
 pub fn expand_path(path: &str) -> Result<PathBuf> {
     let expanded = shellexpand::tilde(path);
     Ok(PathBuf::from(expanded.as_ref()))
 }
+
+/// Resolve a path typed by the user against the current directory, so it
+/// stays valid from anywhere. Absolute and `~` paths are kept as written.
+pub fn absolute_path(path: &str) -> Result<String> {
+    if expand_path(path)?.is_absolute() {
+        return Ok(path.to_string());
+    }
+    let absolute: PathBuf = std::env::current_dir()?.join(path).components().collect();
+    Ok(absolute.to_string_lossy().to_string())
+}
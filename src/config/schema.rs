@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::config::loader::CURRENT_VERSION;
 use crate::error::{ProjectError, Result};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,12 +16,61 @@ pub struct Config {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub default_output_format: OutputFormat,
+    #[serde(default)]
+    pub naming: NamingRules,
+    /// How many backups of the config file to keep; 0 disables backups.
+    #[serde(default = "default_max_backups")]
+    pub max_backups: usize,
+}
+
+fn default_max_backups() -> usize {
+    10
+}
+
+/// Rules project names have to follow.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NamingRules {
+    /// Characters allowed in addition to letters and digits.
+    pub allowed_chars: String,
+    pub max_length: usize,
+    /// Names that can't be used, compared case-insensitively.
+    pub reserved_names: Vec<String>,
+}
+
+impl Default for NamingRules {
+    fn default() -> Self {
+        NamingRules {
+            allowed_chars: "-_.".to_string(),
+            max_length: 64,
+            reserved_names: Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Hosting {
     pub base_path: String,
     pub url_pattern: String,
+    /// Default layout for worktree projects on this hosting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_layout: Option<WorktreeLayout>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthSettings>,
+}
+
+/// How to authenticate against a hosting's remotes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthSettings {
+    /// SSH private keys to try after the ssh-agent (default: ~/.ssh/id_*).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ssh_keys: Vec<String>,
+    /// Environment variable holding an HTTPS access token (default: PM_GIT_TOKEN).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+    /// Username sent with SSH keys or the token, if the URL has none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,8 +81,18 @@ pub struct Project {
     pub is_worktree: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub worktree_branch: Option<String>,
+    /// Branches of additional worktrees created next to the default branch.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub worktrees: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_layout: Option<WorktreeLayout>,
     pub hosting: String,
     pub local_path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// How `pm update` integrates upstream changes unless `--strategy` is given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_strategy: Option<UpdateStrategy>,
     pub created_at: DateTime<Utc>,
     pub last_updated: DateTime<Utc>,
 }
@@ -45,6 +105,32 @@ pub enum OutputFormat {
     Yaml,
 }
 
+/// How the checkout of a worktree project is laid out under `local_path`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WorktreeLayout {
+    /// Regular clone in `<local_path>/<default_branch>`, other worktrees hang off it.
+    #[default]
+    Nested,
+    /// Bare repository in `<local_path>/.bare` with every branch as a linked worktree.
+    Bare,
+}
+
+/// How `pm update` integrates fetched changes into the current branch.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpdateStrategy {
+    /// Only fast-forward; fail if the branch has diverged.
+    #[default]
+    FfOnly,
+    /// Rebase local commits onto the upstream.
+    Rebase,
+    /// Merge the upstream into the branch.
+    Merge,
+    /// Fetch without touching the branch.
+    FetchOnly,
+}
+
 impl Default for Config {
     fn default() -> Self {
         let mut hostings = HashMap::new();
@@ -54,6 +140,8 @@ impl Default for Config {
             Hosting {
                 base_path: "~/github.com".to_string(),
                 url_pattern: "github.com".to_string(),
+                worktree_layout: None,
+                auth: None,
             },
         );
 
@@ -62,6 +150,8 @@ impl Default for Config {
             Hosting {
                 base_path: "~/gitlab.com".to_string(),
                 url_pattern: "gitlab.com".to_string(),
+                worktree_layout: None,
+                auth: None,
             },
         );
 
@@ -70,6 +160,8 @@ impl Default for Config {
             Hosting {
                 base_path: "~/azure.com".to_string(),
                 url_pattern: "azure.com".to_string(),
+                worktree_layout: None,
+                auth: None,
             },
         );
 
@@ -78,13 +170,17 @@ impl Default for Config {
             Hosting {
                 base_path: "~/git".to_string(),
                 url_pattern: String::new(),
+                worktree_layout: None,
+                auth: None,
             },
         );
 
         Config {
-            version: "1.0".to_string(),
+            version: CURRENT_VERSION.to_string(),
             settings: Settings {
                 default_output_format: OutputFormat::Table,
+                naming: NamingRules::default(),
+                max_backups: default_max_backups(),
             },
             hostings,
             projects: Vec::new(),
@@ -106,6 +202,18 @@ impl Config {
         "custom".to_string()
     }
 
+    /// Layout for a worktree project: the project's own setting wins over
+    /// the hosting default.
+    pub fn worktree_layout(
+        &self,
+        project_layout: Option<WorktreeLayout>,
+        hosting: &str,
+    ) -> WorktreeLayout {
+        project_layout
+            .or_else(|| self.hostings.get(hosting).and_then(|h| h.worktree_layout))
+            .unwrap_or_default()
+    }
+
     pub fn find_project(&self, name: &str) -> Option<&Project> {
         self.projects.iter().find(|p| p.name == name)
     }
@@ -141,13 +249,23 @@ impl Project {
             repository_url: None,
             is_worktree: false,
             worktree_branch: None,
+            worktrees: Vec::new(),
+            worktree_layout: None,
             hosting,
             local_path,
+            tags: Vec::new(),
+            update_strategy: None,
             created_at: now,
             last_updated: now,
         }
     }
 
+    pub fn add_tag(&mut self, tag: &str) {
+        if !self.tags.iter().any(|t| t == tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
     pub fn update_timestamp(&mut self) {
         self.last_updated = Utc::now();
     }
@@ -156,31 +274,37 @@ impl Project {
     /// For worktree projects, this returns the path to the branch subdirectory.
     /// For regular projects, this returns the local_path as-is.
     pub fn get_repo_path(&self) -> String {
-        if self.is_worktree {
-            if let Some(ref branch) = self.worktree_branch {
-                // Append the branch name to local_path
-                let path = std::path::Path::new(&self.local_path).join(branch);
-                path.to_string_lossy().to_string()
-            } else {
-                // Fallback: scan for subdirectories with .git if branch name not stored
-                // This handles legacy projects created before worktree_branch was added
-                if let Ok(entries) = std::fs::read_dir(&self.local_path) {
-                    for entry in entries.flatten() {
-                        if let Ok(file_type) = entry.file_type() {
-                            if file_type.is_dir() {
-                                let subdir = entry.path();
-                                if subdir.join(".git").exists() {
-                                    return subdir.to_string_lossy().to_string();
-                                }
-                            }
-                        }
-                    }
-                }
-                // If no subdirectory with .git found, return local_path
-                self.local_path.clone()
+        match self.worktree_branch {
+            Some(ref branch) if self.is_worktree => self.worktree_path(branch),
+            _ => self.local_path.clone(),
+        }
+    }
+
+    /// Path of the worktree for `branch`, a sibling of the default branch
+    /// checkout under `local_path`.
+    pub fn worktree_path(&self, branch: &str) -> String {
+        std::path::Path::new(&self.local_path)
+            .join(branch)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub fn has_worktree(&self, branch: &str) -> bool {
+        self.worktree_branch.as_deref() == Some(branch)
+            || self.worktrees.iter().any(|b| b == branch)
+    }
+
+    /// Like `get_repo_path`, but targets the worktree for `branch` when given.
+    pub fn get_worktree_repo_path(&self, branch: Option<&str>) -> Result<String> {
+        match branch {
+            None => Ok(self.get_repo_path()),
+            Some(branch) if self.worktree_branch.as_deref() == Some(branch) => {
+                Ok(self.get_repo_path())
             }
-        } else {
-            self.local_path.clone()
+            Some(branch) if self.has_worktree(branch) => Ok(self.worktree_path(branch)),
+            Some(branch) => Err(
+                ProjectError::WorktreeNotFound(self.name.clone(), branch.to_string()).into(),
+            ),
         }
     }
 }
@@ -28,6 +28,12 @@ pub enum PmError {
 
     #[error("Dialog error: {0}")]
     Dialog(#[from] dialoguer::Error),
+
+    #[error("Command failed in {0} project(s)")]
+    ExecFailed(usize),
+
+    #[error("Update failed in {0} project(s)")]
+    UpdateFailed(usize),
 }
 
 #[derive(Error, Debug)]
@@ -38,11 +44,20 @@ pub enum ConfigError {
     #[error("Invalid config file: {0}")]
     Invalid(String),
 
+    #[error("Config file already exists at {0}. Use --force to overwrite.")]
+    AlreadyExists(String),
+
     #[error("Failed to create config directory: {0}")]
     DirectoryCreation(String),
 
     #[error("Failed to parse config path: {0}")]
     PathParsing(String),
+
+    #[error("Config version {0} is newer than the supported version {1}; upgrade pm")]
+    UnsupportedVersion(String, String),
+
+    #[error("Backup '{0}' not found")]
+    BackupNotFound(String),
 }
 
 #[derive(Error, Debug)]
@@ -50,6 +65,15 @@ pub enum ProjectError {
     #[error("Project '{0}' not found")]
     NotFound(String),
 
+    #[error("Project '{0}' not found. Did you mean: {1}?")]
+    NotFoundDidYouMean(String, String),
+
+    #[error("'{0}' matches several projects: {1}")]
+    Ambiguous(String, String),
+
+    #[error("No project selected")]
+    NoneSelected,
+
     #[error("Project '{0}' already exists")]
     AlreadyExists(String),
 
@@ -64,6 +88,21 @@ pub enum ProjectError {
 
     #[error("Failed to compute project path: {0}")]
     PathComputation(String),
+
+    #[error("Invalid selector: {0}")]
+    InvalidSelector(String),
+
+    #[error("Project '{0}' is not a worktree project")]
+    NotWorktree(String),
+
+    #[error("Worktree '{1}' not found for project '{0}'")]
+    WorktreeNotFound(String, String),
+
+    #[error("Worktree '{1}' already exists for project '{0}'")]
+    WorktreeAlreadyExists(String, String),
+
+    #[error("Path '{0}' already exists")]
+    PathExists(String),
 }
 
 pub type Result<T> = std::result::Result<T, PmError>;
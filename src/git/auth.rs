@@ -0,0 +1,183 @@
+use git2::{Cred, CredentialType, FetchOptions, RemoteCallbacks};
+use std::path::PathBuf;
+
+use crate::config::paths::expand_path;
+use crate::config::schema::AuthSettings;
+use crate::config::Config;
+
+/// Environment variable with an HTTPS token used when a hosting configures none.
+pub const TOKEN_ENV: &str = "PM_GIT_TOKEN";
+
+/// Key files tried when a hosting doesn't list its own.
+const DEFAULT_SSH_KEYS: &[&str] = &["~/.ssh/id_ed25519", "~/.ssh/id_ecdsa", "~/.ssh/id_rsa"];
+
+/// How to authenticate against the remotes of one hosting.
+///
+/// SSH remotes try the ssh-agent, then each key file. HTTPS remotes try the
+/// token environment variable, then git's credential helpers.
+#[derive(Debug, Clone)]
+pub struct Credentials {
+    ssh_keys: Vec<PathBuf>,
+    token_env: String,
+    username: Option<String>,
+}
+
+impl Credentials {
+    pub fn new(settings: Option<&AuthSettings>) -> Self {
+        let ssh_keys = match settings {
+            Some(s) if !s.ssh_keys.is_empty() => s.ssh_keys.clone(),
+            _ => DEFAULT_SSH_KEYS.iter().map(|k| k.to_string()).collect(),
+        };
+
+        Credentials {
+            ssh_keys: ssh_keys
+                .iter()
+                .filter_map(|key| expand_path(key).ok())
+                .collect(),
+            token_env: settings
+                .and_then(|s| s.token_env.clone())
+                .unwrap_or_else(|| TOKEN_ENV.to_string()),
+            username: settings.and_then(|s| s.username.clone()),
+        }
+    }
+
+    /// Credentials configured for `hosting`, or the defaults.
+    pub fn for_hosting(config: &Config, hosting: &str) -> Self {
+        Self::new(config.hostings.get(hosting).and_then(|h| h.auth.as_ref()))
+    }
+
+    /// Callbacks that authenticate and, if `show_progress` is set, print
+    /// transfer progress.
+    pub fn remote_callbacks(&self, show_progress: bool) -> RemoteCallbacks<'_> {
+        let mut callbacks = RemoteCallbacks::new();
+        let mut attempt = Attempt::default();
+
+        callbacks.credentials(move |url, username_from_url, allowed| {
+            attempt.next(self, url, username_from_url, allowed)
+        });
+
+        if show_progress {
+            callbacks.transfer_progress(|stats| {
+                if stats.received_objects() == stats.total_objects() {
+                    print!(
+                        "Resolving deltas {}/{}\r",
+                        stats.indexed_deltas(),
+                        stats.total_deltas()
+                    );
+                } else if stats.total_objects() > 0 {
+                    print!(
+                        "Receiving objects {}/{}\r",
+                        stats.received_objects(),
+                        stats.total_objects()
+                    );
+                }
+                std::io::Write::flush(&mut std::io::stdout()).unwrap();
+                true
+            });
+        }
+
+        callbacks
+    }
+
+    pub fn fetch_options(&self, show_progress: bool) -> FetchOptions<'_> {
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(self.remote_callbacks(show_progress));
+        fetch_options
+    }
+}
+
+/// Progress through the authentication methods. libgit2 calls the
+/// credentials callback again after every rejected credential, so each
+/// method is offered at most once.
+#[derive(Default)]
+struct Attempt {
+    ssh_agent: bool,
+    ssh_key: usize,
+    token: bool,
+    helper: bool,
+    username: bool,
+    tried: Vec<String>,
+}
+
+impl Attempt {
+    fn next(
+        &mut self,
+        creds: &Credentials,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed: CredentialType,
+    ) -> Result<Cred, git2::Error> {
+        let user = creds
+            .username
+            .as_deref()
+            .or(username_from_url)
+            .unwrap_or("git");
+
+        if allowed.contains(CredentialType::USERNAME) && !self.username {
+            self.username = true;
+            return Cred::username(user);
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            // Without an agent socket libgit2 fails outright instead of
+            // letting us fall through to the key files.
+            let has_agent = cfg!(windows) || std::env::var_os("SSH_AUTH_SOCK").is_some();
+            if !self.ssh_agent && has_agent {
+                self.ssh_agent = true;
+                self.tried.push("ssh-agent".to_string());
+                if let Ok(cred) = Cred::ssh_key_from_agent(user) {
+                    return Ok(cred);
+                }
+            }
+
+            while self.ssh_key < creds.ssh_keys.len() {
+                let key = &creds.ssh_keys[self.ssh_key];
+                self.ssh_key += 1;
+                if key.is_file() {
+                    self.tried.push(format!("key {}", key.display()));
+                    return Cred::ssh_key(user, None, key, None);
+                }
+            }
+        }
+
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            if !self.token {
+                self.token = true;
+                if let Ok(token) = std::env::var(&creds.token_env) {
+                    self.tried.push(format!("token from ${}", creds.token_env));
+                    return Cred::userpass_plaintext(user, &token);
+                }
+            }
+
+            if !self.helper {
+                self.helper = true;
+                if let Ok(config) = git2::Config::open_default() {
+                    if let Ok(cred) = Cred::credential_helper(&config, url, username_from_url) {
+                        self.tried.push("git credential helper".to_string());
+                        return Ok(cred);
+                    }
+                }
+            }
+        }
+
+        if allowed.contains(CredentialType::DEFAULT) && self.tried.is_empty() {
+            self.tried.push("default credentials".to_string());
+            return Cred::default();
+        }
+
+        let tried = if allowed.contains(CredentialType::SSH_KEY) && self.tried.is_empty() {
+            "no ssh-agent running and no key file found".to_string()
+        } else if self.tried.is_empty() {
+            format!(
+                "no credentials found; set ${} or configure a git credential helper",
+                creds.token_env
+            )
+        } else {
+            format!("tried {}", self.tried.join(", "))
+        };
+        Err(git2::Error::from_str(&format!(
+            "Authentication failed for {} ({})",
+            url, tried
+        )))
+    }
+}
@@ -1,82 +1,116 @@
-use git2::{FetchOptions, RemoteCallbacks};
+use git2::BranchType;
 use std::path::Path;
 
+use crate::config::WorktreeLayout;
 use crate::error::Result;
-
-pub fn clone_repository(url: &str, path: &Path, is_worktree: bool) -> Result<Option<String>> {
-    if is_worktree {
-        let default_branch = detect_default_branch(url)?;
+use crate::git::auth::Credentials;
+use crate::git::worktree::add_worktree;
+
+/// Directory holding the bare repository in the `bare` worktree layout.
+pub const BARE_DIR: &str = ".bare";
+
+/// Clone `url` into `path`, laid out for worktrees when `is_worktree` is set.
+/// Returns the default branch for worktree projects. `show_progress` prints
+/// transfer progress, which only makes sense for one clone at a time.
+pub fn clone_repository(
+    url: &str,
+    path: &Path,
+    is_worktree: bool,
+    layout: WorktreeLayout,
+    credentials: &Credentials,
+    show_progress: bool,
+) -> Result<Option<String>> {
+    if is_worktree && layout == WorktreeLayout::Bare {
+        clone_bare_worktree(url, path, credentials, show_progress).map(Some)
+    } else if is_worktree {
+        let default_branch = detect_default_branch(url, credentials)?;
         let worktree_path = path.join(&default_branch);
 
         std::fs::create_dir_all(&worktree_path)?;
 
-        let mut callbacks = RemoteCallbacks::new();
-        callbacks.transfer_progress(|stats| {
-            if stats.received_objects() == stats.total_objects() {
-                print!(
-                    "Resolving deltas {}/{}\r",
-                    stats.indexed_deltas(),
-                    stats.total_deltas()
-                );
-            } else if stats.total_objects() > 0 {
-                print!(
-                    "Receiving objects {}/{}\r",
-                    stats.received_objects(),
-                    stats.total_objects()
-                );
-            }
-            std::io::Write::flush(&mut std::io::stdout()).unwrap();
-            true
-        });
-
-        let mut fetch_options = FetchOptions::new();
-        fetch_options.remote_callbacks(callbacks);
-
         let mut builder = git2::build::RepoBuilder::new();
-        builder.fetch_options(fetch_options);
+        builder.fetch_options(credentials.fetch_options(show_progress));
 
         builder.clone(url, &worktree_path)?;
-        println!();
+        end_progress(show_progress);
 
         Ok(Some(default_branch))
     } else {
         std::fs::create_dir_all(path)?;
 
-        let mut callbacks = RemoteCallbacks::new();
-        callbacks.transfer_progress(|stats| {
-            if stats.received_objects() == stats.total_objects() {
-                print!(
-                    "Resolving deltas {}/{}\r",
-                    stats.indexed_deltas(),
-                    stats.total_deltas()
-                );
-            } else if stats.total_objects() > 0 {
-                print!(
-                    "Receiving objects {}/{}\r",
-                    stats.received_objects(),
-                    stats.total_objects()
-                );
-            }
-            std::io::Write::flush(&mut std::io::stdout()).unwrap();
-            true
-        });
-
-        let mut fetch_options = FetchOptions::new();
-        fetch_options.remote_callbacks(callbacks);
-
         let mut builder = git2::build::RepoBuilder::new();
-        builder.fetch_options(fetch_options);
+        builder.fetch_options(credentials.fetch_options(show_progress));
 
         builder.clone(url, path)?;
-        println!();
+        end_progress(show_progress);
 
         Ok(None)
     }
 }
 
-fn detect_default_branch(url: &str) -> Result<String> {
+/// Clone into `<path>/.bare`, point `<path>/.git` at it and check out the
+/// default branch as a linked worktree in `<path>/<default_branch>`.
+fn clone_bare_worktree(
+    url: &str,
+    path: &Path,
+    credentials: &Credentials,
+    show_progress: bool,
+) -> Result<String> {
+    let bare_path = path.join(BARE_DIR);
+    std::fs::create_dir_all(&bare_path)?;
+
+    let mut builder = git2::build::RepoBuilder::new();
+    builder.bare(true);
+    builder.fetch_options(credentials.fetch_options(show_progress));
+
+    let repo = builder.clone(url, &bare_path)?;
+    end_progress(show_progress);
+
+    std::fs::write(path.join(".git"), format!("gitdir: ./{}\n", BARE_DIR))?;
+
+    // Bare clones don't keep remote-tracking branches, which worktrees need
+    // for upstream tracking and `pm update`.
+    let refspec = "+refs/heads/*:refs/remotes/origin/*";
+    let has_refspec = repo
+        .find_remote("origin")?
+        .fetch_refspecs()?
+        .iter()
+        .any(|r| r == Some(refspec));
+    if !has_refspec {
+        repo.remote_add_fetch("origin", refspec)?;
+    }
+    repo.find_remote("origin")?
+        .fetch(&[] as &[&str], Some(&mut credentials.fetch_options(show_progress)), None)?;
+    end_progress(show_progress);
+
+    let default_branch = repo
+        .head()?
+        .shorthand()
+        .ok_or_else(|| git2::Error::from_str("Could not determine default branch"))?
+        .to_string();
+
+    let mut branch = repo.find_branch(&default_branch, BranchType::Local)?;
+    branch.set_upstream(Some(&format!("origin/{}", default_branch)))?;
+
+    add_worktree(&bare_path, &default_branch, &path.join(&default_branch))?;
+
+    Ok(default_branch)
+}
+
+/// Move past the `\r`-redrawn progress line.
+fn end_progress(show_progress: bool) {
+    if show_progress {
+        println!();
+    }
+}
+
+fn detect_default_branch(url: &str, credentials: &Credentials) -> Result<String> {
     let mut remote = git2::Remote::create_detached(url)?;
-    let connection = remote.connect_auth(git2::Direction::Fetch, None, None)?;
+    let connection = remote.connect_auth(
+        git2::Direction::Fetch,
+        Some(credentials.remote_callbacks(false)),
+        None,
+    )?;
 
     let default_branch = connection
         .default_branch()?
@@ -0,0 +1,180 @@
+use git2::Repository;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::config::WorktreeLayout;
+use crate::error::Result;
+use crate::git::clone::BARE_DIR;
+
+/// A repository found on disk, described in the terms `Project` uses.
+#[derive(Debug)]
+pub struct DiscoveredRepo {
+    pub local_path: PathBuf,
+    pub repository_url: Option<String>,
+    pub is_worktree: bool,
+    pub layout: Option<WorktreeLayout>,
+    pub worktree_branch: Option<String>,
+    pub worktrees: Vec<String>,
+}
+
+enum Detection {
+    Found(DiscoveredRepo),
+    /// Part of a repository found elsewhere, e.g. a linked worktree.
+    Ignore,
+    NotRepository,
+}
+
+/// Walk `root` up to `max_depth` levels deep and collect git repositories.
+/// Directories inside a detected repository are not searched further.
+pub fn discover_repositories(root: &Path, max_depth: usize) -> Vec<DiscoveredRepo> {
+    let mut repos = Vec::new();
+    let mut walker = WalkDir::new(root)
+        .max_depth(max_depth)
+        .sort_by_file_name()
+        .into_iter();
+
+    while let Some(entry) = walker.next() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if entry.depth() > 0 && hidden {
+            walker.skip_current_dir();
+            continue;
+        }
+
+        match detect(entry.path()) {
+            Ok(Detection::Found(repo)) => {
+                repos.push(repo);
+                walker.skip_current_dir();
+            }
+            Ok(Detection::Ignore) => walker.skip_current_dir(),
+            Ok(Detection::NotRepository) | Err(_) => {}
+        }
+    }
+
+    repos
+}
+
+fn detect(dir: &Path) -> Result<Detection> {
+    if dir.join(BARE_DIR).is_dir() {
+        return detect_bare_layout(dir).map(Detection::Found);
+    }
+
+    let dot_git = dir.join(".git");
+    if dot_git.is_file() {
+        // Linked worktree; it is picked up through its main repository.
+        return Ok(Detection::Ignore);
+    }
+    if !dot_git.is_dir() {
+        return Ok(Detection::NotRepository);
+    }
+
+    let repo = Repository::open(dir)?;
+    let branch = current_branch(&repo);
+
+    // Nested worktree layout: the main checkout lives in `<local_path>/<branch>`.
+    if let Some(ref branch) = branch {
+        if dir.ends_with(branch) {
+            let local_path = strip_branch(dir, branch);
+            let worktrees = linked_worktree_branches(&repo, &local_path);
+            return Ok(Detection::Found(DiscoveredRepo {
+                local_path,
+                repository_url: origin_url(&repo),
+                is_worktree: true,
+                layout: Some(WorktreeLayout::Nested),
+                worktree_branch: Some(branch.clone()),
+                worktrees,
+            }));
+        }
+    }
+
+    Ok(Detection::Found(DiscoveredRepo {
+        local_path: dir.to_path_buf(),
+        repository_url: origin_url(&repo),
+        is_worktree: false,
+        layout: None,
+        worktree_branch: None,
+        worktrees: Vec::new(),
+    }))
+}
+
+fn detect_bare_layout(dir: &Path) -> Result<DiscoveredRepo> {
+    let repo = Repository::open_bare(dir.join(BARE_DIR))?;
+    let mut worktrees = linked_worktree_branches(&repo, dir);
+
+    // Prefer the default branch as the primary worktree.
+    let default_branch = current_branch(&repo);
+    let worktree_branch = match default_branch {
+        Some(ref branch) if worktrees.contains(branch) => default_branch.clone(),
+        _ => worktrees.first().cloned(),
+    };
+    worktrees.retain(|b| Some(b) != worktree_branch.as_ref());
+
+    Ok(DiscoveredRepo {
+        local_path: dir.to_path_buf(),
+        repository_url: origin_url(&repo),
+        is_worktree: true,
+        layout: Some(WorktreeLayout::Bare),
+        worktree_branch,
+        worktrees,
+    })
+}
+
+fn current_branch(repo: &Repository) -> Option<String> {
+    repo.head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(|s| s.to_string()))
+}
+
+fn origin_url(repo: &Repository) -> Option<String> {
+    repo.find_remote("origin")
+        .ok()
+        .and_then(|remote| remote.url().map(|s| s.to_string()))
+}
+
+/// Branches of linked worktrees that sit at `<local_path>/<branch>`.
+fn linked_worktree_branches(repo: &Repository, local_path: &Path) -> Vec<String> {
+    let mut branches = Vec::new();
+
+    let names = match repo.worktrees() {
+        Ok(names) => names,
+        Err(_) => return branches,
+    };
+
+    for name in names.iter().flatten() {
+        let worktree = match repo.find_worktree(name) {
+            Ok(worktree) => worktree,
+            Err(_) => continue,
+        };
+        let wt_repo = match Repository::open_from_worktree(&worktree) {
+            Ok(wt_repo) => wt_repo,
+            Err(_) => continue,
+        };
+        if let Some(branch) = current_branch(&wt_repo) {
+            let expected: PathBuf = local_path.join(&branch);
+            let actual: PathBuf = worktree.path().components().collect();
+            if actual == expected {
+                branches.push(branch);
+            }
+        }
+    }
+
+    branches.sort();
+    branches
+}
+
+fn strip_branch(dir: &Path, branch: &str) -> PathBuf {
+    let mut local_path = dir.to_path_buf();
+    for _ in Path::new(branch).components() {
+        local_path.pop();
+    }
+    local_path
+}
@@ -1,7 +1,13 @@
+pub mod auth;
 pub mod clone;
+pub mod discover;
+pub mod relocate;
 pub mod status;
 pub mod update;
+pub mod worktree;
 
+pub use auth::Credentials;
 pub use clone::clone_repository;
+pub use relocate::move_checkout;
 pub use status::get_repository_status;
 pub use update::update_repository;
@@ -0,0 +1,146 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::error::{ProjectError, Result};
+
+/// How deep below the moved directory to look for `.git` entries. Enough for
+/// `<root>/<branch>/.git` and `<root>/.bare`.
+const LINK_SEARCH_DEPTH: usize = 3;
+
+/// Move a checkout to a new location and repair git worktree links.
+///
+/// Git records absolute paths in both directions between a repository and its
+/// linked worktrees (`<worktree>/.git` and `<gitdir>/worktrees/<name>/gitdir`),
+/// so every link that pointed into `from` is rewritten to point into `to`.
+pub fn move_checkout(from: &Path, to: &Path) -> Result<()> {
+    if to.exists() {
+        return Err(ProjectError::PathExists(to.display().to_string()).into());
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Links may have been recorded through symlinks or with the resolved path.
+    let canonical_from = from.canonicalize()?;
+    fs::rename(from, to)?;
+    let canonical_to = to.canonicalize()?;
+
+    let repaired = repair_worktree_links(to, from, to).and_then(|_| {
+        if canonical_from != from {
+            repair_worktree_links(to, &canonical_from, &canonical_to)?;
+        }
+        Ok(())
+    });
+
+    if let Err(e) = repaired {
+        // Put the checkout back where git still expects it.
+        if fs::rename(to, from).is_ok() {
+            let _ = repair_worktree_links(from, to, from);
+            let _ = repair_worktree_links(from, &canonical_to, &canonical_from);
+        }
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// Rewrite worktree links found under `root` that point below `old` to point
+/// below `new` instead.
+pub fn repair_worktree_links(root: &Path, old: &Path, new: &Path) -> Result<()> {
+    let entries = WalkDir::new(root)
+        .max_depth(LINK_SEARCH_DEPTH)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !is_git_internal(e.path()))
+        .filter_map(|e| e.ok());
+
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name();
+
+        if name == ".git" && entry.file_type().is_file() {
+            repair_worktree_file(path, old, new)?;
+        } else if (name == ".git" || name == ".bare") && entry.file_type().is_dir() {
+            repair_admin_dirs(path, old, new)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fix a linked worktree's `.git` file and the matching admin `gitdir` entry.
+fn repair_worktree_file(git_file: &Path, old: &Path, new: &Path) -> Result<()> {
+    let content = fs::read_to_string(git_file)?;
+    let gitdir = match content.trim().strip_prefix("gitdir:") {
+        Some(gitdir) => PathBuf::from(gitdir.trim()),
+        None => return Ok(()),
+    };
+
+    // Relative links (such as the bare layout's `gitdir: ./.bare`) move along.
+    if gitdir.is_relative() {
+        return Ok(());
+    }
+
+    let gitdir = match rebase(&gitdir, old, new) {
+        Some(rebased) => {
+            fs::write(git_file, format!("gitdir: {}\n", rebased.display()))?;
+            rebased
+        }
+        None => gitdir,
+    };
+
+    let back_link = gitdir.join("gitdir");
+    if back_link.is_file() {
+        fs::write(&back_link, format!("{}\n", git_file.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Fix the `worktrees/*/{gitdir,commondir}` entries of a repository and the
+/// `.git` files of worktrees that live outside the moved directory.
+fn repair_admin_dirs(git_dir: &Path, old: &Path, new: &Path) -> Result<()> {
+    let worktrees = git_dir.join("worktrees");
+    if !worktrees.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(&worktrees)? {
+        let admin = entry?.path();
+
+        // libgit2 records the common directory as an absolute path.
+        let common_link = admin.join("commondir");
+        if let Ok(content) = fs::read_to_string(&common_link) {
+            if let Some(rebased) = rebase(Path::new(content.trim()), old, new) {
+                fs::write(&common_link, format!("{}\n", rebased.display()))?;
+            }
+        }
+
+        let back_link = admin.join("gitdir");
+        let target = match fs::read_to_string(&back_link) {
+            Ok(content) => PathBuf::from(content.trim()),
+            Err(_) => continue,
+        };
+
+        if let Some(rebased) = rebase(&target, old, new) {
+            fs::write(&back_link, format!("{}\n", rebased.display()))?;
+        } else if target.is_file() {
+            // The worktree stayed put; point it at the moved admin directory.
+            fs::write(&target, format!("gitdir: {}\n", admin.display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `path` is inside a git directory, where no worktree can live.
+fn is_git_internal(path: &Path) -> bool {
+    path.parent()
+        .and_then(|p| p.file_name())
+        .is_some_and(|name| name == ".git" || name == ".bare")
+}
+
+fn rebase(path: &Path, old: &Path, new: &Path) -> Option<PathBuf> {
+    path.strip_prefix(old).ok().map(|rest| new.join(rest))
+}
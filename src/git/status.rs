@@ -1,53 +1,175 @@
-use git2::Repository;
+use git2::{Branch, ErrorCode, Repository, RepositoryState};
+use serde::Serialize;
+use std::fmt;
 use std::path::Path;
 
 use crate::error::Result;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct RepoStatus {
     pub has_changes: bool,
     pub staged: usize,
     pub modified: usize,
     pub untracked: usize,
+    /// Files with unresolved merge conflicts in the index.
+    pub conflicted: usize,
+    /// Current branch, also set for unborn branches. `None` when detached.
+    pub branch: Option<String>,
+    /// Upstream tracking branch, e.g. `origin/main`.
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub detached: bool,
+    pub stash_count: usize,
+    pub operation: Option<RepoOperation>,
+}
+
+/// An operation that was started but not yet finished in the repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+    ApplyMailbox,
+}
+
+impl fmt::Display for RepoOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            RepoOperation::Merge => "merging",
+            RepoOperation::Rebase => "rebasing",
+            RepoOperation::CherryPick => "cherry-picking",
+            RepoOperation::Revert => "reverting",
+            RepoOperation::Bisect => "bisecting",
+            RepoOperation::ApplyMailbox => "applying patches",
+        };
+        write!(f, "{}", label)
+    }
 }
 
 pub fn get_repository_status(path: &Path) -> Result<RepoStatus> {
-    let repo = Repository::open(path)?;
-    let statuses = repo.statuses(None)?;
+    let mut repo = Repository::open(path)?;
 
     let mut staged = 0;
     let mut modified = 0;
     let mut untracked = 0;
+    let mut conflicted = 0;
 
-    for entry in statuses.iter() {
-        let status = entry.status();
+    {
+        let statuses = repo.statuses(None)?;
 
-        if status.is_index_new()
-            || status.is_index_modified()
-            || status.is_index_deleted()
-            || status.is_index_renamed()
-            || status.is_index_typechange()
-        {
-            staged += 1;
-        }
+        for entry in statuses.iter() {
+            let status = entry.status();
 
-        if status.is_wt_modified()
-            || status.is_wt_deleted()
-            || status.is_wt_renamed()
-            || status.is_wt_typechange()
-        {
-            modified += 1;
-        }
+            if status.is_index_new()
+                || status.is_index_modified()
+                || status.is_index_deleted()
+                || status.is_index_renamed()
+                || status.is_index_typechange()
+            {
+                staged += 1;
+            }
 
-        if status.is_wt_new() {
-            untracked += 1;
+            if status.is_wt_modified()
+                || status.is_wt_deleted()
+                || status.is_wt_renamed()
+                || status.is_wt_typechange()
+            {
+                modified += 1;
+            }
+
+            if status.is_wt_new() {
+                untracked += 1;
+            }
+
+            if status.is_conflicted() {
+                conflicted += 1;
+            }
         }
     }
 
+    let detached = repo.head_detached()?;
+    let (branch, upstream, ahead, behind) = branch_tracking(&repo)?;
+    let operation = current_operation(&repo);
+
+    let mut stash_count = 0;
+    repo.stash_foreach(|_, _, _| {
+        stash_count += 1;
+        true
+    })?;
+
     Ok(RepoStatus {
-        has_changes: staged > 0 || modified > 0 || untracked > 0,
+        has_changes: staged > 0 || modified > 0 || untracked > 0 || conflicted > 0,
         staged,
         modified,
         untracked,
+        conflicted,
+        branch,
+        upstream,
+        ahead,
+        behind,
+        detached,
+        stash_count,
+        operation,
     })
 }
+
+/// Resolve the current branch, its upstream and how far the two have diverged.
+fn branch_tracking(repo: &Repository) -> Result<(Option<String>, Option<String>, usize, usize)> {
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e) if e.code() == ErrorCode::UnbornBranch => {
+            // No commits yet: HEAD still names the branch it will create.
+            let branch = repo
+                .find_reference("HEAD")?
+                .symbolic_target()
+                .map(|target| target.trim_start_matches("refs/heads/").to_string());
+            return Ok((branch, None, 0, 0));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if !head.is_branch() {
+        return Ok((None, None, 0, 0));
+    }
+
+    let branch_name = head.shorthand().map(|s| s.to_string());
+    let local_oid = head.target();
+    let branch = Branch::wrap(head);
+
+    let upstream = match branch.upstream() {
+        Ok(upstream) => upstream,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok((branch_name, None, 0, 0)),
+        Err(e) => return Err(e.into()),
+    };
+
+    let upstream_name = upstream.name()?.map(|s| s.to_string());
+
+    let (ahead, behind) = match (local_oid, upstream.get().target()) {
+        (Some(local), Some(remote)) => repo.graph_ahead_behind(local, remote)?,
+        _ => (0, 0),
+    };
+
+    Ok((branch_name, upstream_name, ahead, behind))
+}
+
+fn current_operation(repo: &Repository) -> Option<RepoOperation> {
+    match repo.state() {
+        RepositoryState::Clean => None,
+        RepositoryState::Merge => Some(RepoOperation::Merge),
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some(RepoOperation::Revert),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+            Some(RepoOperation::CherryPick)
+        }
+        RepositoryState::Bisect => Some(RepoOperation::Bisect),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => Some(RepoOperation::Rebase),
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => {
+            Some(RepoOperation::ApplyMailbox)
+        }
+    }
+}
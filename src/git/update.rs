@@ -1,62 +1,370 @@
-use git2::{FetchOptions, RemoteCallbacks, Repository};
+use git2::{
+    build::CheckoutBuilder, AnnotatedCommit, CheckoutNotificationType, ErrorCode, Oid, Rebase,
+    Repository, Signature, StashFlags,
+};
+use std::fmt;
 use std::path::Path;
 
+use crate::config::UpdateStrategy;
 use crate::error::Result;
+use crate::git::auth::Credentials;
+use crate::git::status::get_repository_status;
 
-pub fn update_repository(path: &Path) -> Result<()> {
-    let repo = Repository::open(path)?;
-
-    let mut remote = repo.find_remote("origin")?;
-
-    let mut callbacks = RemoteCallbacks::new();
-    callbacks.transfer_progress(|stats| {
-        if stats.received_objects() == stats.total_objects() {
-            print!(
-                "Resolving deltas {}/{}\r",
-                stats.indexed_deltas(),
-                stats.total_deltas()
-            );
-        } else if stats.total_objects() > 0 {
-            print!(
-                "Receiving objects {}/{}\r",
-                stats.received_objects(),
-                stats.total_objects()
-            );
-        }
-        std::io::Write::flush(&mut std::io::stdout()).unwrap();
-        true
-    });
+/// How to update a repository.
+#[derive(Debug, Clone, Copy)]
+pub struct UpdateOptions {
+    pub strategy: UpdateStrategy,
+    /// Stash local changes before integrating and restore them afterwards.
+    pub autostash: bool,
+    /// Print transfer progress. Off when several repositories are updated at
+    /// once, since the `\r` updates would garble each other.
+    pub show_progress: bool,
+}
+
+/// What `update_repository` did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    UpToDate,
+    Fetched,
+    FastForwarded,
+    Merged,
+    Rebased,
+    /// Left alone because tracked files have uncommitted changes.
+    SkippedDirty,
+    /// Left alone because HEAD is not on a branch.
+    SkippedDetached,
+    /// Left alone because the branch has no upstream configured.
+    SkippedNoUpstream,
+}
+
+impl UpdateOutcome {
+    pub fn is_skipped(&self) -> bool {
+        matches!(
+            self,
+            UpdateOutcome::SkippedDirty
+                | UpdateOutcome::SkippedDetached
+                | UpdateOutcome::SkippedNoUpstream
+        )
+    }
+}
+
+impl fmt::Display for UpdateOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            UpdateOutcome::UpToDate => "up to date",
+            UpdateOutcome::Fetched => "fetched",
+            UpdateOutcome::FastForwarded => "fast-forward",
+            UpdateOutcome::Merged => "merged",
+            UpdateOutcome::Rebased => "rebased",
+            UpdateOutcome::SkippedDirty => "skipped (dirty)",
+            UpdateOutcome::SkippedDetached => "skipped (detached HEAD)",
+            UpdateOutcome::SkippedNoUpstream => "skipped (no upstream)",
+        };
+        f.write_str(text)
+    }
+}
+
+/// Fetch the current branch's upstream and integrate it using the
+/// configured strategy. Repositories with uncommitted changes in tracked
+/// files are skipped unless `autostash` is set, as are detached HEADs and
+/// branches without an upstream.
+pub fn update_repository(
+    path: &Path,
+    options: &UpdateOptions,
+    credentials: &Credentials,
+) -> Result<UpdateOutcome> {
+    let status = get_repository_status(path)?;
+    if let Some(operation) = status.operation {
+        return Err(git2::Error::from_str(&format!(
+            "Repository is in the middle of {}; finish or abort it first",
+            operation
+        ))
+        .into());
+    }
+
+    let branch_name = match status.branch {
+        Some(branch) if !status.detached => branch,
+        _ => return Ok(UpdateOutcome::SkippedDetached),
+    };
+
+    // Untracked files survive every strategy; safe checkout reports the ones
+    // an update would overwrite.
+    let dirty = status.staged > 0 || status.modified > 0;
+    if dirty && !options.autostash && options.strategy != UpdateStrategy::FetchOnly {
+        return Ok(UpdateOutcome::SkippedDirty);
+    }
+
+    if status.conflicted > 0 {
+        return Err(git2::Error::from_str(
+            "Repository has unresolved conflicts; resolve them first",
+        )
+        .into());
+    }
 
-    let mut fetch_options = FetchOptions::new();
-    fetch_options.remote_callbacks(callbacks);
+    let mut repo = Repository::open(path)?;
+    let refname = format!("refs/heads/{}", branch_name);
 
-    remote.fetch(&[] as &[&str], Some(&mut fetch_options), None)?;
-    println!();
+    let upstream = match resolve_upstream(&repo, &refname)? {
+        Some(upstream) => upstream,
+        None => return Ok(UpdateOutcome::SkippedNoUpstream),
+    };
 
-    let head = repo.head()?;
-    let branch_name = head
-        .shorthand()
-        .ok_or_else(|| git2::Error::from_str("Could not determine current branch"))?;
+    // A remote of "." tracks another local branch; there is nothing to fetch.
+    if upstream.remote != "." {
+        let mut remote = repo.find_remote(&upstream.remote)?;
+        let mut fetch_options = credentials.fetch_options(options.show_progress);
+        remote.fetch(&[] as &[&str], Some(&mut fetch_options), None)?;
+        if options.show_progress {
+            println!();
+        }
+    }
 
-    let fetch_head = repo.find_reference("FETCH_HEAD")?;
-    let fetch_commit = repo.reference_to_annotated_commit(&fetch_head)?;
+    if options.strategy == UpdateStrategy::FetchOnly {
+        return Ok(UpdateOutcome::Fetched);
+    }
 
-    let (analysis, _) = repo.merge_analysis(&[&fetch_commit])?;
+    let target = match repo.refname_to_id(&upstream.tracking_ref) {
+        Ok(oid) => oid,
+        Err(e) if e.code() == ErrorCode::NotFound => {
+            return Err(git2::Error::from_str(&format!(
+                "Upstream branch '{}' does not exist; it may have been deleted on the remote",
+                upstream.short_name()
+            ))
+            .into())
+        }
+        Err(e) => return Err(e.into()),
+    };
 
+    let (analysis, _) = repo.merge_analysis(&[&repo.find_annotated_commit(target)?])?;
     if analysis.is_up_to_date() {
-        return Ok(());
-    } else if analysis.is_fast_forward() {
-        let refname = format!("refs/heads/{}", branch_name);
-        let mut reference = repo.find_reference(&refname)?;
-        reference.set_target(fetch_commit.id(), "Fast-forward")?;
-        repo.set_head(&refname)?;
-        repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))?;
-    } else {
+        return Ok(UpdateOutcome::UpToDate);
+    }
+
+    let fast_forwardable = analysis.is_fast_forward() || analysis.is_unborn();
+    if !fast_forwardable && options.strategy == UpdateStrategy::FfOnly {
         return Err(git2::Error::from_str(
-            "Fast-forward only merge not possible. Manual merge required.",
+            "Fast-forward not possible; use --strategy rebase or merge",
         )
         .into());
     }
 
+    // An unborn branch has nothing to stash against, and the safe checkout
+    // onto it reports any local file it would overwrite.
+    let stashed = if dirty && !analysis.is_unborn() {
+        let signature = signature(&repo)?;
+        repo.stash_save(&signature, "pm update: autostash", Some(StashFlags::DEFAULT))?;
+        true
+    } else {
+        false
+    };
+
+    let result = if fast_forwardable {
+        fast_forward(&repo, &refname, target).map(|_| UpdateOutcome::FastForwarded)
+    } else {
+        let theirs = repo.find_annotated_commit(target)?;
+        match options.strategy {
+            UpdateStrategy::Rebase => rebase(&repo, &theirs).map(|_| UpdateOutcome::Rebased),
+            _ => {
+                let message = format!(
+                    "Merge remote-tracking branch '{}' into {}",
+                    upstream.short_name(),
+                    branch_name
+                );
+                merge(&repo, &message, &theirs).map(|_| UpdateOutcome::Merged)
+            }
+        }
+    };
+
+    if stashed {
+        restore_stash(&mut repo, &result)?;
+    }
+
+    result
+}
+
+/// Re-apply the autostash on top of the updated branch. The stash is only
+/// dropped after it applied cleanly, so conflicting changes stay recoverable.
+fn restore_stash(repo: &mut Repository, result: &Result<UpdateOutcome>) -> Result<()> {
+    let applied = repo.stash_apply(0, None);
+    let conflicted = repo.index()?.has_conflicts();
+
+    let problem = match applied {
+        Ok(()) if !conflicted => {
+            repo.stash_drop(0)?;
+            return Ok(());
+        }
+        Ok(()) => "conflicts with the update".to_string(),
+        Err(e) => format!("failed ({})", e.message()),
+    };
+    let done = match result {
+        Ok(outcome) => format!("Updated ({}), but restoring", outcome),
+        Err(_) => "Restoring".to_string(),
+    };
+
+    Err(git2::Error::from_str(&format!(
+        "{} local changes {}; they are kept in stash@{{0}}",
+        done, problem
+    ))
+    .into())
+}
+
+/// Where a local branch pulls from, as configured by `branch.<name>.remote`
+/// and `branch.<name>.merge`.
+struct Upstream {
+    remote: String,
+    /// The local ref the upstream is fetched into, e.g. `refs/remotes/origin/main`.
+    tracking_ref: String,
+}
+
+impl Upstream {
+    fn short_name(&self) -> &str {
+        self.tracking_ref
+            .strip_prefix("refs/remotes/")
+            .or_else(|| self.tracking_ref.strip_prefix("refs/heads/"))
+            .unwrap_or(&self.tracking_ref)
+    }
+}
+
+/// Look up the upstream of the local branch `refname`, or `None` when the
+/// branch does not track anything.
+fn resolve_upstream(repo: &Repository, refname: &str) -> Result<Option<Upstream>> {
+    let remote = match repo.branch_upstream_remote(refname) {
+        Ok(remote) => remote,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let tracking_ref = match repo.branch_upstream_name(refname) {
+        Ok(name) => name,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    match (remote.as_str(), tracking_ref.as_str()) {
+        (Some(remote), Some(tracking_ref)) => Ok(Some(Upstream {
+            remote: remote.to_string(),
+            tracking_ref: tracking_ref.to_string(),
+        })),
+        _ => Err(git2::Error::from_str("Upstream configuration is not valid UTF-8").into()),
+    }
+}
+
+/// Check out `target` and move the branch to it. The checkout is safe: if
+/// it would overwrite files with local changes, nothing is touched and the
+/// files are reported.
+fn fast_forward(repo: &Repository, refname: &str, target: Oid) -> Result<()> {
+    let commit = repo.find_commit(target)?;
+    let mut conflicts = Vec::new();
+
+    let checkout = {
+        let mut checkout = CheckoutBuilder::new();
+        checkout
+            .safe()
+            .notify_on(CheckoutNotificationType::CONFLICT)
+            .notify(|_, path, _, _, _| {
+                if let Some(path) = path {
+                    conflicts.push(path.display().to_string());
+                }
+                true
+            });
+        repo.checkout_tree(commit.as_object(), Some(&mut checkout))
+    };
+
+    if let Err(e) = checkout {
+        if conflicts.is_empty() {
+            return Err(e.into());
+        }
+        return Err(git2::Error::from_str(&format!(
+            "Update would overwrite local files: {}",
+            conflicts.join(", ")
+        ))
+        .into());
+    }
+
+    // `reference` also creates the branch when it is still unborn.
+    repo.reference(refname, target, true, "pm update: fast-forward")?;
+    repo.set_head(refname)?;
+    Ok(())
+}
+
+/// Merge `upstream` into the current branch, backing out on conflicts or
+/// any other failure.
+fn merge(repo: &Repository, message: &str, upstream: &AnnotatedCommit) -> Result<()> {
+    if let Err(e) = commit_merge(repo, message, upstream) {
+        abort_merge(repo)?;
+        return Err(e);
+    }
     Ok(())
 }
+
+fn commit_merge(repo: &Repository, message: &str, upstream: &AnnotatedCommit) -> Result<()> {
+    repo.merge(&[upstream], None, None)?;
+
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        return Err(git2::Error::from_str("Merge has conflicts; merge manually").into());
+    }
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let theirs = repo.find_commit(upstream.id())?;
+    let signature = signature(repo)?;
+
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &[&head, &theirs],
+    )?;
+    repo.cleanup_state()?;
+    Ok(())
+}
+
+fn abort_merge(repo: &Repository) -> Result<()> {
+    repo.cleanup_state()?;
+    let head = repo.head()?.peel_to_commit()?;
+    repo.reset(head.as_object(), git2::ResetType::Hard, None)?;
+    Ok(())
+}
+
+/// Replay local commits on top of `upstream`, aborting on conflicts or any
+/// other failure.
+fn rebase(repo: &Repository, upstream: &AnnotatedCommit) -> Result<()> {
+    let signature = signature(repo)?;
+    let mut rebase = repo.rebase(None, Some(upstream), None, None)?;
+
+    if let Err(e) = replay(repo, &mut rebase, &signature) {
+        rebase.abort()?;
+        return Err(e);
+    }
+
+    rebase.finish(Some(&signature))?;
+    Ok(())
+}
+
+fn replay(repo: &Repository, rebase: &mut Rebase, signature: &Signature) -> Result<()> {
+    while let Some(operation) = rebase.next() {
+        operation?;
+
+        if repo.index()?.has_conflicts() {
+            return Err(git2::Error::from_str("Rebase has conflicts; rebase manually").into());
+        }
+
+        match rebase.commit(None, signature, None) {
+            Ok(_) => {}
+            // The change is already upstream.
+            Err(e) if e.code() == ErrorCode::Applied => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+/// The user's git identity, or a placeholder for repositories without one.
+fn signature(repo: &Repository) -> Result<Signature<'static>> {
+    match repo.signature() {
+        Ok(signature) => Ok(signature),
+        Err(_) => Ok(Signature::now("pm", "pm@localhost")?),
+    }
+}
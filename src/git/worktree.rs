@@ -0,0 +1,158 @@
+use git2::{BranchType, Repository, WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions};
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::git::get_repository_status;
+
+#[derive(Debug)]
+pub struct WorktreeInfo {
+    pub path: PathBuf,
+    pub branch: Option<String>,
+    pub is_main: bool,
+    pub locked: bool,
+    pub prunable: bool,
+}
+
+/// Git stores worktree metadata under `.git/worktrees/<name>`, so the name
+/// cannot contain path separators even though branch names can.
+pub fn worktree_name(branch: &str) -> String {
+    branch.replace('/', "-")
+}
+
+/// Open the repository shared by all worktrees of `path`: the main
+/// repository for nested layouts and the bare repository for bare layouts.
+pub fn open_common_repository(path: &Path) -> Result<Repository> {
+    let repo = Repository::open(path)?;
+    if !repo.is_worktree() {
+        return Ok(repo);
+    }
+
+    // A linked worktree's git dir records the shared git dir in `commondir`,
+    // usually relative to itself.
+    let git_dir = repo.path();
+    let common_dir = std::fs::read_to_string(git_dir.join("commondir"))?;
+    Ok(Repository::open(git_dir.join(common_dir.trim()))?)
+}
+
+/// Add a worktree for `branch` at `worktree_path`.
+/// The local branch is created from `origin/<branch>` when only the remote
+/// branch exists, or from HEAD when the branch does not exist at all.
+pub fn add_worktree(repo_path: &Path, branch: &str, worktree_path: &Path) -> Result<()> {
+    let repo = open_common_repository(repo_path)?;
+
+    let local_branch = match repo.find_branch(branch, BranchType::Local) {
+        Ok(local_branch) => local_branch,
+        Err(_) => {
+            let remote_name = format!("origin/{}", branch);
+            match repo.find_branch(&remote_name, BranchType::Remote) {
+                Ok(remote_branch) => {
+                    let commit = remote_branch.get().peel_to_commit()?;
+                    let mut local_branch = repo.branch(branch, &commit, false)?;
+                    local_branch.set_upstream(Some(&remote_name))?;
+                    local_branch
+                }
+                Err(_) => {
+                    let commit = repo.head()?.peel_to_commit()?;
+                    repo.branch(branch, &commit, false)?
+                }
+            }
+        }
+    };
+
+    if let Some(parent) = worktree_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let reference = local_branch.into_reference();
+    let mut options = WorktreeAddOptions::new();
+    options.reference(Some(&reference));
+
+    repo.worktree(&worktree_name(branch), worktree_path, Some(&options))?;
+    Ok(())
+}
+
+/// List the main working tree followed by all linked worktrees.
+pub fn list_worktrees(repo_path: &Path) -> Result<Vec<WorktreeInfo>> {
+    let repo = open_common_repository(repo_path)?;
+    let mut worktrees = Vec::new();
+
+    if let Some(workdir) = repo.workdir() {
+        let branch = repo
+            .head()
+            .ok()
+            .filter(|head| head.is_branch())
+            .and_then(|head| head.shorthand().map(|s| s.to_string()));
+        worktrees.push(WorktreeInfo {
+            // `workdir` carries a trailing separator; normalize it for display.
+            path: workdir.components().collect(),
+            branch,
+            is_main: true,
+            locked: false,
+            prunable: false,
+        });
+    }
+
+    for name in repo.worktrees()?.iter().flatten() {
+        let worktree = repo.find_worktree(name)?;
+
+        let branch = Repository::open_from_worktree(&worktree)
+            .ok()
+            .and_then(|wt_repo| {
+                wt_repo
+                    .head()
+                    .ok()
+                    .filter(|head| head.is_branch())
+                    .and_then(|head| head.shorthand().map(|s| s.to_string()))
+            });
+
+        worktrees.push(WorktreeInfo {
+            path: worktree.path().to_path_buf(),
+            branch,
+            is_main: false,
+            locked: matches!(worktree.is_locked()?, WorktreeLockStatus::Locked(_)),
+            prunable: worktree.validate().is_err(),
+        });
+    }
+
+    Ok(worktrees)
+}
+
+/// Remove the worktree for `branch`, deleting its working directory.
+/// Refuses to discard local changes unless `force` is set.
+pub fn remove_worktree(repo_path: &Path, branch: &str, force: bool) -> Result<()> {
+    let repo = open_common_repository(repo_path)?;
+    let worktree = repo.find_worktree(&worktree_name(branch))?;
+
+    if !force && worktree.validate().is_ok() {
+        let status = get_repository_status(worktree.path())?;
+        if status.has_changes {
+            return Err(git2::Error::from_str(&format!(
+                "Worktree '{}' has local changes. Use --force to remove it anyway.",
+                branch
+            ))
+            .into());
+        }
+    }
+
+    let mut options = WorktreePruneOptions::new();
+    options.valid(true).working_tree(true).locked(force);
+    worktree.prune(Some(&mut options))?;
+    Ok(())
+}
+
+/// Drop git's metadata for worktrees whose directories no longer exist.
+/// Returns the names of the pruned worktrees.
+pub fn prune_worktrees(repo_path: &Path) -> Result<Vec<String>> {
+    let repo = open_common_repository(repo_path)?;
+    let mut pruned = Vec::new();
+
+    for name in repo.worktrees()?.iter().flatten() {
+        let worktree = repo.find_worktree(name)?;
+        if worktree.is_prunable(None)? {
+            worktree.prune(None)?;
+            pruned.push(name.to_string());
+        }
+    }
+
+    Ok(pruned)
+}
@@ -5,12 +5,17 @@ mod error;
 mod git;
 mod models;
 mod output;
+mod parallel;
 
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
 use cli::{Cli, Commands};
 use error::Result;
 
 fn main() {
+    // Answer completion requests from the scripts printed by `pm completions`.
+    CompleteEnv::with_factory(Cli::command).complete();
+
     if let Err(e) = run() {
         eprintln!("Error: {}", e);
         std::process::exit(1);
@@ -21,15 +26,24 @@ fn run() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Init(args) => commands::init::execute(args),
+        Commands::Init(ref args) => commands::init::execute(args, &cli),
         Commands::Create(ref args) => commands::create::execute(args, &cli),
         Commands::List(ref args) => commands::list::execute(args, &cli),
         Commands::Delete(ref args) => commands::delete::execute(args, &cli),
         Commands::Edit(ref args) => commands::edit::execute(args, &cli),
+        Commands::Mv(ref args) => commands::mv::execute(args, &cli),
         Commands::Status(ref args) => commands::status::execute(args, &cli),
         Commands::Update(ref args) => commands::update::execute(args, &cli),
+        Commands::Clone(ref args) => commands::clone::execute(args, &cli),
+        Commands::Exec(ref args) => commands::exec::execute(args, &cli),
         Commands::Search(ref args) => commands::search::execute(args, &cli),
-        Commands::Navigate(args) => commands::navigate::execute(args),
-        Commands::Completions(args) => commands::completions::execute(args),
+        Commands::Import(ref args) => commands::import::execute(args, &cli),
+        Commands::Worktree(ref args) => commands::worktree::execute(args, &cli),
+        Commands::Navigate(ref args) => commands::navigate::execute(args, &cli),
+        Commands::Config(ref args) => commands::config::execute(args, &cli),
+        Commands::Completions(ref args) => commands::completions::execute(args),
+        Commands::ShellInit(ref args) => commands::shell_init::execute(args),
+        Commands::Complete(ref args) => commands::complete::execute(args, &cli),
+        Commands::Prompt => commands::prompt::execute(&cli),
     }
 }
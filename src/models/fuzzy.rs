@@ -0,0 +1,74 @@
+use crate::config::Project;
+
+/// Projects matching `query`, best first.
+///
+/// A case-insensitive exact name match wins outright. Otherwise prefix
+/// matches rank above substring matches, which rank above subsequence
+/// matches (`pyapi` finds `payments-api`); ties go to the shorter name.
+pub fn rank_matches<'p>(query: &str, projects: &'p [Project]) -> Vec<&'p Project> {
+    let query = query.to_lowercase();
+
+    if let Some(project) = projects.iter().find(|p| p.name.to_lowercase() == query) {
+        return vec![project];
+    }
+
+    let mut ranked: Vec<(usize, &Project)> = projects
+        .iter()
+        .filter_map(|project| {
+            let name = project.name.to_lowercase();
+            let tier = if name.starts_with(&query) {
+                0
+            } else if name.contains(&query) {
+                1
+            } else if is_subsequence(&query, &name) {
+                2
+            } else {
+                return None;
+            };
+            Some((tier, project))
+        })
+        .collect();
+
+    ranked.sort_by_key(|(tier, project)| (*tier, project.name.len()));
+    ranked.into_iter().map(|(_, project)| project).collect()
+}
+
+/// Names close to `query` by edit distance, for "did you mean" hints.
+pub fn suggestions(query: &str, projects: &[Project], limit: usize) -> Vec<String> {
+    let query = query.to_lowercase();
+    let max_distance = (query.chars().count() / 3).max(2);
+
+    let mut scored: Vec<(usize, &str)> = projects
+        .iter()
+        .map(|p| (levenshtein(&query, &p.name.to_lowercase()), p.name.as_str()))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+
+    scored.sort();
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
@@ -1,3 +1,8 @@
+pub mod fuzzy;
 pub mod project;
+pub mod selector;
+pub mod validation;
 
 pub use project::compute_project_path;
+pub use selector::Selector;
+pub use validation::{sanitize_project_name, validate_project_name};
@@ -0,0 +1,392 @@
+//! Project selection shared by every command that operates on many projects.
+//!
+//! A selector is a whitespace-separated list of terms that must all match:
+//!
+//! - `hosting:<glob>`, `tag:<glob>`, `name:<glob>`, `desc:<glob>`,
+//!   `path:<glob>`, `branch:<glob>` match project attributes (`*` and `?`
+//!   are wildcards, matching is case-insensitive)
+//! - `worktree`, `cloned`, `dirty`, `clean`, `unpushed`, `behind` test the
+//!   project kind or the state of its checkout
+//! - any other word matches part of the name or description
+//! - a leading `!` negates a term
+//!
+//! On the command line each argument is one term. In a `pm search` pattern,
+//! values with spaces can be double-quoted.
+
+use crate::config::paths::expand_path;
+use crate::config::Project;
+use crate::error::{ProjectError, Result};
+use crate::git::get_repository_status;
+use crate::git::status::RepoStatus;
+
+/// Keys accepted in `key:value` terms, including aliases.
+const SELECTOR_KEYS: &[&str] = &[
+    "hosting",
+    "host",
+    "tag",
+    "name",
+    "desc",
+    "description",
+    "path",
+    "branch",
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct Selector {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    negated: bool,
+    predicate: Predicate,
+}
+
+impl Term {
+    fn parse(token: &str) -> Result<Self> {
+        let (negated, token) = match token.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, token),
+        };
+        if token.is_empty() {
+            return Err(ProjectError::InvalidSelector("'!' without a term".to_string()).into());
+        }
+        Ok(Term {
+            negated,
+            predicate: Predicate::parse(token)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Predicate {
+    Hosting(String),
+    Tag(String),
+    Name(String),
+    Description(String),
+    Path(String),
+    Branch(String),
+    Text(String),
+    /// Case-sensitive variant of `Text`.
+    ExactText(String),
+    Worktree,
+    Cloned,
+    Dirty,
+    Clean,
+    Unpushed,
+    Behind,
+}
+
+impl Predicate {
+    fn parse(token: &str) -> Result<Self> {
+        if let Some((key, value)) = token.split_once(':') {
+            let value = value.to_lowercase();
+            if value.is_empty() {
+                return Err(
+                    ProjectError::InvalidSelector(format!("missing value for '{}:'", key)).into(),
+                );
+            }
+            return match key {
+                "hosting" | "host" => Ok(Predicate::Hosting(value)),
+                "tag" => Ok(Predicate::Tag(value)),
+                "name" => Ok(Predicate::Name(value)),
+                "desc" | "description" => Ok(Predicate::Description(value)),
+                "path" => Ok(Predicate::Path(shellexpand::tilde(&value).to_string())),
+                "branch" => Ok(Predicate::Branch(value)),
+                _ => Err(ProjectError::InvalidSelector(format!("unknown key '{}'", key)).into()),
+            };
+        }
+
+        Ok(match token {
+            "worktree" => Predicate::Worktree,
+            "cloned" => Predicate::Cloned,
+            "dirty" => Predicate::Dirty,
+            "clean" => Predicate::Clean,
+            "unpushed" => Predicate::Unpushed,
+            "behind" => Predicate::Behind,
+            text => Predicate::Text(text.to_lowercase()),
+        })
+    }
+
+    fn needs_status(&self) -> bool {
+        matches!(
+            self,
+            Predicate::Cloned
+                | Predicate::Dirty
+                | Predicate::Clean
+                | Predicate::Unpushed
+                | Predicate::Behind
+        )
+    }
+
+    fn matches(&self, project: &Project, state: &CheckoutState) -> bool {
+        match self {
+            Predicate::Hosting(pattern) => glob_match(pattern, &project.hosting),
+            Predicate::Tag(pattern) => project.tags.iter().any(|t| glob_match(pattern, t)),
+            Predicate::Name(pattern) => glob_match(pattern, &project.name),
+            Predicate::Description(pattern) => project
+                .description
+                .as_deref()
+                .is_some_and(|d| glob_match(pattern, d)),
+            Predicate::Path(pattern) => expand_path(&project.local_path)
+                .is_ok_and(|path| glob_match(pattern, &path.to_string_lossy())),
+            Predicate::Branch(pattern) => project
+                .worktree_branch
+                .iter()
+                .chain(project.worktrees.iter())
+                .any(|b| glob_match(pattern, b)),
+            Predicate::Text(text) => {
+                project.name.to_lowercase().contains(text)
+                    || project
+                        .description
+                        .as_deref()
+                        .is_some_and(|d| d.to_lowercase().contains(text))
+            }
+            Predicate::ExactText(text) => {
+                project.name.contains(text.as_str())
+                    || project
+                        .description
+                        .as_deref()
+                        .is_some_and(|d| d.contains(text.as_str()))
+            }
+            Predicate::Worktree => project.is_worktree,
+            Predicate::Cloned => state.cloned(),
+            Predicate::Dirty => state.status().is_some_and(|s| s.has_changes),
+            Predicate::Clean => state.status().is_some_and(|s| !s.has_changes),
+            Predicate::Unpushed => state.status().is_some_and(|s| s.ahead > 0),
+            Predicate::Behind => state.status().is_some_and(|s| s.behind > 0),
+        }
+    }
+}
+
+/// Checkout state of a project, only inspected when a term needs it.
+enum CheckoutState {
+    Unknown,
+    Missing,
+    Present(Option<RepoStatus>),
+}
+
+impl CheckoutState {
+    fn load(project: &Project) -> Self {
+        match expand_path(&project.get_repo_path()) {
+            Ok(path) if path.exists() => CheckoutState::Present(get_repository_status(&path).ok()),
+            _ => CheckoutState::Missing,
+        }
+    }
+
+    fn cloned(&self) -> bool {
+        matches!(self, CheckoutState::Present(_))
+    }
+
+    fn status(&self) -> Option<&RepoStatus> {
+        match self {
+            CheckoutState::Present(status) => status.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl Selector {
+    /// Build the selector for a `pm search` pattern. Only `key:value` terms
+    /// with a known key act as selector terms; the rest of the pattern is
+    /// text to find in the name or description, so patterns such as
+    /// `http://host` keep working. The text is matched case-sensitively
+    /// unless `ignore_case` is set.
+    pub fn search(pattern: &str, ignore_case: bool) -> Result<Self> {
+        let mut selector = Selector::default();
+        let mut words = Vec::new();
+
+        // An unbalanced quote just makes the whole pattern plain text.
+        for token in tokenize(pattern).unwrap_or_default() {
+            if is_keyed_term(&token) {
+                selector.terms.push(Term::parse(&token)?);
+            } else {
+                words.push(token);
+            }
+        }
+
+        let text = if selector.terms.is_empty() {
+            pattern.to_string()
+        } else {
+            words.join(" ")
+        };
+        if !text.is_empty() {
+            let predicate = if ignore_case {
+                Predicate::Text(text.to_lowercase())
+            } else {
+                Predicate::ExactText(text)
+            };
+            selector.push(predicate, false);
+        }
+
+        Ok(selector)
+    }
+
+    /// Build a selector from command-line arguments, one term per argument.
+    /// The shell has already done the quoting, so `'desc:hello world'` is a
+    /// single term.
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let terms = args
+            .iter()
+            .map(|arg| Term::parse(arg))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Selector { terms })
+    }
+
+    pub fn hosting(mut self, hosting: Option<&str>) -> Self {
+        if let Some(hosting) = hosting {
+            self.push(Predicate::Hosting(escape(hosting)), false);
+        }
+        self
+    }
+
+    pub fn name(mut self, name: Option<&str>) -> Self {
+        if let Some(name) = name {
+            self.push(Predicate::Name(escape(name)), false);
+        }
+        self
+    }
+
+    pub fn text(mut self, text: Option<&str>) -> Self {
+        if let Some(text) = text {
+            self.push(Predicate::Text(text.to_lowercase()), false);
+        }
+        self
+    }
+
+    pub fn tags(mut self, tags: &[String]) -> Self {
+        for tag in tags {
+            self.push(Predicate::Tag(escape(tag)), false);
+        }
+        self
+    }
+
+    pub fn branch(mut self, branch: Option<&str>) -> Self {
+        if let Some(branch) = branch {
+            self.push(Predicate::Branch(escape(branch)), false);
+        }
+        self
+    }
+
+    /// Require (`Some(true)`) or exclude (`Some(false)`) worktree projects.
+    pub fn worktree(mut self, worktree: Option<bool>) -> Self {
+        if let Some(worktree) = worktree {
+            self.push(Predicate::Worktree, !worktree);
+        }
+        self
+    }
+
+    fn push(&mut self, predicate: Predicate, negated: bool) {
+        self.terms.push(Term { negated, predicate });
+    }
+
+    pub fn matches(&self, project: &Project) -> bool {
+        let mut state = CheckoutState::Unknown;
+
+        self.terms.iter().all(|term| {
+            if term.predicate.needs_status() && matches!(state, CheckoutState::Unknown) {
+                state = CheckoutState::load(project);
+            }
+            term.predicate.matches(project, &state) != term.negated
+        })
+    }
+
+    pub fn select<'p>(&self, projects: &'p [Project]) -> Vec<&'p Project> {
+        projects.iter().filter(|p| self.matches(p)).collect()
+    }
+}
+
+/// Whether `token` is a `key:value` term with a key the selector knows.
+fn is_keyed_term(token: &str) -> bool {
+    let token = token.strip_prefix('!').unwrap_or(token);
+    token
+        .split_once(':')
+        .is_some_and(|(key, _)| SELECTOR_KEYS.contains(&key))
+}
+
+/// Split on whitespace, keeping double-quoted sections together.
+fn tokenize(expr: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in expr.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(ProjectError::InvalidSelector("unterminated quote".to_string()).into());
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    Ok(tokens)
+}
+
+/// Values from exact-match flags such as `--name` must not act as wildcards.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.to_lowercase().chars() {
+        if matches!(c, '*' | '?' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Case-insensitive glob match supporting `*`, `?` and `\` escapes.
+/// `pattern` is expected to be lowercase already.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+                continue;
+            }
+            Some('?') => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            Some('\\') if pattern.get(p + 1) == Some(&text[t]) => {
+                p += 2;
+                t += 1;
+                continue;
+            }
+            Some(&c) if c != '\\' && c == text[t] => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match backtrack {
+            Some((star_p, star_t)) => {
+                p = star_p + 1;
+                t = star_t + 1;
+                backtrack = Some((star_p, star_t + 1));
+            }
+            None => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,82 @@
+use crate::config::NamingRules;
+use crate::error::{PmError, ProjectError, Result};
+
+/// Check a project name against the configured naming rules.
+///
+/// Names always have to start with a letter or digit, so they can't be
+/// mistaken for flags or hidden directories; everything else is configurable.
+pub fn validate_project_name(name: &str, rules: &NamingRules) -> Result<()> {
+    let first = match name.chars().next() {
+        Some(c) => c,
+        None => return Err(invalid(name, "is empty")),
+    };
+
+    if !first.is_alphanumeric() {
+        return Err(invalid(name, "must start with a letter or digit"));
+    }
+
+    if let Some(c) = name.chars().find(|&c| !is_allowed(c, rules)) {
+        let allowed = if rules.allowed_chars.is_empty() {
+            "letters and digits".to_string()
+        } else {
+            format!("letters, digits and '{}'", rules.allowed_chars)
+        };
+        return Err(invalid(
+            name,
+            &format!("contains {:?}; only {} are allowed", c, allowed),
+        ));
+    }
+
+    let length = name.chars().count();
+    if length > rules.max_length {
+        return Err(invalid(
+            name,
+            &format!(
+                "is {} characters long, the limit is {}",
+                length, rules.max_length
+            ),
+        ));
+    }
+
+    if rules
+        .reserved_names
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(name))
+    {
+        return Err(invalid(name, "is reserved"));
+    }
+
+    Ok(())
+}
+
+/// Turn an arbitrary string (such as a directory name) into a name that
+/// passes the charset and length rules by replacing disallowed characters
+/// with `-`. Reserved names are left for `validate_project_name` to reject.
+pub fn sanitize_project_name(name: &str, rules: &NamingRules) -> String {
+    let replacement = if rules.allowed_chars.contains('-') {
+        "-"
+    } else {
+        ""
+    };
+
+    let mut sanitized = String::new();
+    for c in name.chars() {
+        if is_allowed(c, rules) {
+            sanitized.push(c);
+        } else if !sanitized.is_empty() && !sanitized.ends_with(replacement) {
+            sanitized.push_str(replacement);
+        }
+    }
+
+    let sanitized = sanitized.trim_start_matches(|c: char| !c.is_alphanumeric());
+    let sanitized: String = sanitized.chars().take(rules.max_length).collect();
+    sanitized.trim_end_matches(replacement).to_string()
+}
+
+fn is_allowed(c: char, rules: &NamingRules) -> bool {
+    c.is_alphanumeric() || rules.allowed_chars.contains(c)
+}
+
+fn invalid(name: &str, reason: &str) -> PmError {
+    ProjectError::InvalidName(format!("'{}' {}", name, reason)).into()
+}
@@ -1,6 +1,17 @@
-use crate::config::Project;
+use crate::cli::{Cli, OutputFormatArg};
+use crate::config::{Config, OutputFormat, Project};
 use crate::error::Result;
 
 pub trait Formatter {
     fn format(&self, projects: &[Project]) -> Result<String>;
 }
+
+/// Pick the output format from `--output`, falling back to the configured default.
+pub fn resolve_output_format(cli: &Cli, config: &Config) -> OutputFormat {
+    match cli.output {
+        Some(OutputFormatArg::Table) => OutputFormat::Table,
+        Some(OutputFormatArg::Json) => OutputFormat::Json,
+        Some(OutputFormatArg::Yaml) => OutputFormat::Yaml,
+        None => config.settings.default_output_format,
+    }
+}
@@ -3,7 +3,7 @@ pub mod json;
 pub mod table;
 pub mod yaml;
 
-pub use formatter::Formatter;
+pub use formatter::{resolve_output_format, Formatter};
 pub use json::JsonFormatter;
 pub use table::TableFormatter;
 pub use yaml::YamlFormatter;
@@ -15,6 +15,8 @@ struct ProjectRow {
     hosting: String,
     #[tabled(rename = "WORKTREE")]
     worktree: String,
+    #[tabled(rename = "TAGS")]
+    tags: String,
     #[tabled(rename = "PATH")]
     path: String,
 }
@@ -34,6 +36,11 @@ impl Formatter for TableFormatter {
                 description: p.description.clone().unwrap_or_else(|| "-".to_string()),
                 hosting: p.hosting.clone(),
                 worktree: if p.is_worktree { "yes" } else { "no" }.to_string(),
+                tags: if p.tags.is_empty() {
+                    "-".to_string()
+                } else {
+                    p.tags.join(",")
+                },
                 path: p.local_path.clone(),
             })
             .collect();
@@ -0,0 +1,99 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+/// Number of workers used when no `--jobs` value is given.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Whether git transfer progress should be shown. Progress uses `\r` to
+/// redraw a single line, which only works while one job runs at a time.
+pub fn show_progress(jobs: usize, quiet: bool) -> bool {
+    jobs == 1 && !quiet
+}
+
+/// Run `work` for every item on up to `jobs` worker threads.
+///
+/// `on_done` is invoked on the calling thread as each item finishes, so
+/// callers can report progress without interleaving output. The returned
+/// results keep the order of `items`.
+pub fn map<T, R, F, D>(items: &[T], jobs: usize, work: F, mut on_done: D) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    D: FnMut(&T, &R),
+{
+    map_while(items, jobs, work, |item, result| {
+        on_done(item, result);
+        true
+    })
+    .into_iter()
+    .map(|result| result.expect("every item is processed when not stopped"))
+    .collect()
+}
+
+/// Like [`map`], but stops handing out new items once `on_done` returns
+/// `false`. Items that were never started are returned as `None`; items
+/// already running when the stop was requested still complete.
+pub fn map_while<T, R, F, D>(items: &[T], jobs: usize, work: F, mut on_done: D) -> Vec<Option<R>>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    D: FnMut(&T, &R) -> bool,
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    let mut results: Vec<Option<R>> = items.iter().map(|_| None).collect();
+
+    if jobs == 1 {
+        for (index, item) in items.iter().enumerate() {
+            let result = work(item);
+            let keep_going = on_done(item, &result);
+            results[index] = Some(result);
+            if !keep_going {
+                break;
+            }
+        }
+        return results;
+    }
+
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel();
+
+        for _ in 0..jobs {
+            let tx = tx.clone();
+            let next = &next;
+            let stop = &stop;
+            let work = &work;
+            scope.spawn(move || loop {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                if index >= items.len() {
+                    break;
+                }
+                if tx.send((index, work(&items[index]))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        for (index, result) in rx {
+            if !on_done(&items[index], &result) {
+                stop.store(true, Ordering::SeqCst);
+            }
+            results[index] = Some(result);
+        }
+    });
+
+    results
+}
@@ -1,4 +1,1213 @@
+use std::path::Path;
+
+/// A `pm` command using the config at `config`, with `HOME` set to the
+/// config's directory so the real user config is never touched.
+fn pm(config: &Path, args: &[&str]) -> assert_cmd::Command {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("pm");
+    cmd.env("HOME", config.parent().unwrap())
+        .env_remove("XDG_CONFIG_HOME")
+        .env("PM_CONFIG", config)
+        .args(args);
+    cmd
+}
+
+/// Create a repository at `path` with one commit on `main`.
+fn init_repo(path: &Path) -> git2::Repository {
+    let repo = git2::Repository::init(path).unwrap();
+    std::fs::write(path.join("README"), "hello\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("README")).unwrap();
+    index.write().unwrap();
+    let tree_id = index.write_tree().unwrap();
+    {
+        let tree = repo.find_tree(tree_id).unwrap();
+        let signature = git2::Signature::now("pm", "pm@localhost").unwrap();
+        repo.commit(
+            Some("refs/heads/main"),
+            &signature,
+            &signature,
+            "init",
+            &tree,
+            &[],
+        )
+        .unwrap();
+    }
+    repo.set_head("refs/heads/main").unwrap();
+    repo
+}
+
+/// Commit `content` as `file` on `branch` of the repository at `path`,
+/// leaving its working tree alone.
+fn commit_file(path: &Path, branch: &str, file: &str, content: &str) -> git2::Oid {
+    let repo = git2::Repository::open(path).unwrap();
+    let refname = format!("refs/heads/{}", branch);
+    let parent = repo
+        .find_reference(&refname)
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    let blob = repo.blob(content.as_bytes()).unwrap();
+    let mut builder = repo.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+    builder.insert(file, blob, 0o100644).unwrap();
+    let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+    let signature = git2::Signature::now("pm", "pm@localhost").unwrap();
+    let message = format!("Update {}", file);
+    repo.commit(
+        Some(&refname),
+        &signature,
+        &signature,
+        &message,
+        &tree,
+        &[&parent],
+    )
+    .unwrap()
+}
+
+fn stash_count(path: &Path) -> usize {
+    let mut repo = git2::Repository::open(path).unwrap();
+    let mut count = 0;
+    repo.stash_foreach(|_, _, _| {
+        count += 1;
+        true
+    })
+    .unwrap();
+    count
+}
+
+#[test]
+fn test_custom_config_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("team.toml");
+
+    assert_cmd::cargo::cargo_bin_cmd!("pm")
+        .env("HOME", dir.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["-c", config_path.to_str().unwrap(), "init"])
+        .assert()
+        .success();
+    assert!(config_path.exists());
+
+    assert_cmd::cargo::cargo_bin_cmd!("pm")
+        .env("HOME", dir.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env("PM_CONFIG", &config_path)
+        .args(["create", "api", "-p", "/tmp/api"])
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(&config_path).unwrap();
+    assert!(content.contains("name = \"api\""));
+    assert!(!dir.path().join(".config/pm/projects.toml").exists());
+}
+
+#[test]
+fn test_selector_expression() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+
+    pm(
+        &config,
+        &[
+            "create",
+            "payments-api",
+            "-p",
+            "/tmp/payments-api",
+            "-t",
+            "infra",
+        ],
+    )
+    .assert()
+    .success();
+    pm(
+        &config,
+        &["create", "web", "-p", "/tmp/web", "-t", "frontend"],
+    )
+    .assert()
+    .success();
+    pm(
+        &config,
+        &["create", "infra-api", "-p", "/tmp/infra-api", "-w"],
+    )
+    .assert()
+    .success();
+
+    let output = pm(&config, &["list", "-o", "json", "name:*-api", "!worktree"])
+        .output()
+        .unwrap();
+    let projects: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<&str> = projects
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["payments-api"]);
+
+    pm(&config, &["list", "bogus:value"]).assert().failure();
+
+    // The shell's quoting decides what a term is.
+    pm(&config, &["edit", "web", "-d", "hello world"])
+        .assert()
+        .success();
+    pm(&config, &["list", "desc:hello world"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("web"));
+}
+
+#[test]
+fn test_search_pattern() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+
+    pm(
+        &config,
+        &[
+            "create",
+            "Billing",
+            "-p",
+            "/tmp/billing",
+            "-d",
+            "see http://x/billing",
+        ],
+    )
+    .assert()
+    .success();
+    pm(
+        &config,
+        &["create", "api", "-p", "/tmp/api", "-d", "internal"],
+    )
+    .assert()
+    .success();
+
+    let search = |args: &[&str]| -> Vec<String> {
+        let output = pm(&config, &[&["search", "-o", "json"], args].concat())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let projects: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        projects
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(search(&["http://x"]), vec!["Billing"]);
+    assert!(search(&["billing see"]).is_empty());
+    assert!(search(&["BILL"]).is_empty());
+    assert_eq!(search(&["-i", "BILL"]), vec!["Billing"]);
+    assert_eq!(search(&["in hosting:custom"]), vec!["Billing", "api"]);
+    assert!(search(&["in hosting:github"]).is_empty());
+}
+
+#[test]
+fn test_project_name_validation() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+
+    pm(&config, &["create", "my project", "-p", "/tmp/my-project"])
+        .assert()
+        .failure();
+    pm(&config, &["create", "foo/bar", "-p", "/tmp/foo-bar"])
+        .assert()
+        .failure();
+    pm(&config, &["create", "api", "-p", "/tmp/api"])
+        .assert()
+        .success();
+    pm(&config, &["create", "web", "-p", "/tmp/web"])
+        .assert()
+        .success();
+
+    pm(&config, &["edit", "api", "-n", ".api"])
+        .assert()
+        .failure();
+    pm(&config, &["edit", "api", "-n", "web"])
+        .assert()
+        .failure();
+    pm(&config, &["edit", "api", "-n", "api-v2"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_invalid_config_is_not_overwritten() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("projects.toml");
+    let broken = "version = \"1.1\"\n[settings\ndefault_output_format = \"table\"\n";
+    std::fs::write(&config_path, broken).unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("pm")
+        .env("HOME", dir.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env("PM_CONFIG", &config_path)
+        .args(["create", "api", "-p", "/tmp/api"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("projects.toml:2:"));
+
+    assert_eq!(std::fs::read_to_string(&config_path).unwrap(), broken);
+}
+
+#[test]
+fn test_clone_missing_projects() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    init_repo(&origin);
+    let url = format!("file://{}", origin.display());
+
+    let lib_path = dir.path().join("lib");
+    let wt_path = dir.path().join("wt");
+    pm(
+        &config,
+        &[
+            "create",
+            "lib",
+            "-r",
+            &url,
+            "-p",
+            lib_path.to_str().unwrap(),
+        ],
+    )
+    .assert()
+    .success();
+    pm(
+        &config,
+        &[
+            "create",
+            "wt",
+            "-r",
+            &url,
+            "-w",
+            "-p",
+            wt_path.to_str().unwrap(),
+        ],
+    )
+    .assert()
+    .success();
+
+    pm(&config, &["clone", "-j", "2"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("2 cloned"));
+
+    assert!(lib_path.join("README").exists());
+    assert!(wt_path.join("main/README").exists());
+    let content = std::fs::read_to_string(&config).unwrap();
+    assert!(content.contains("worktree_branch = \"main\""));
+
+    pm(&config, &["clone"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("already cloned"));
+}
+
+#[test]
+fn test_mv_resolves_relative_destination() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let checkout = dir.path().join("n");
+    init_repo(&checkout);
+
+    pm(&config, &["create", "n", "-p", checkout.to_str().unwrap()])
+        .assert()
+        .success();
+    pm(&config, &["mv", "n", "n-moved"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    let moved = dir.path().join("n-moved");
+    assert!(moved.join("README").exists());
+    let content = std::fs::read_to_string(&config).unwrap();
+    assert!(content.contains(&format!("local_path = \"{}\"", moved.display())));
+
+    let output = pm(&config, &["status", "-o", "json"])
+        .current_dir("/")
+        .output()
+        .unwrap();
+    let statuses: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(statuses[0]["state"], "clean");
+}
+
+#[test]
+fn test_mv_repairs_worktree_links() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    init_repo(&origin);
+    let url = format!("file://{}", origin.display());
+
+    for layout in ["nested", "bare"] {
+        let name = format!("wt-{}", layout);
+        let old = dir.path().join(&name);
+        let new = dir.path().join("moved").join(&name);
+
+        pm(
+            &config,
+            &[
+                "create", &name, "-r", &url, "-w", "--layout", layout, "--clone",
+            ],
+        )
+        .args(["-p", old.to_str().unwrap()])
+        .assert()
+        .success();
+        pm(&config, &["worktree", "add", &name, "feature"])
+            .assert()
+            .success();
+        pm(&config, &["mv", &name, new.to_str().unwrap()])
+            .assert()
+            .success();
+        assert!(!old.exists());
+
+        // Both sides of every worktree link point into the new location.
+        let common_dir = match layout {
+            "bare" => new.join(".bare"),
+            _ => new.join("main"),
+        };
+        let common = git2::Repository::open(&common_dir).unwrap();
+        let worktree = common.find_worktree("feature").unwrap();
+        assert!(
+            worktree.validate().is_ok(),
+            "{} worktree is invalid",
+            layout
+        );
+        assert_eq!(worktree.path(), new.join("feature"));
+
+        for branch in ["main", "feature"] {
+            let repo = git2::Repository::open(new.join(branch)).unwrap();
+            assert_eq!(repo.head().unwrap().shorthand(), Some(branch));
+        }
+
+        let output = pm(&config, &["worktree", "list", &name]).output().unwrap();
+        let listing = String::from_utf8(output.stdout).unwrap();
+        assert!(listing.contains(&new.join("feature").display().to_string()));
+        assert!(!listing.contains("prunable"));
+
+        let selector = format!("name:{}", name);
+        let output = pm(
+            &config,
+            &["status", "-o", "json", "-b", "feature", &selector],
+        )
+        .output()
+        .unwrap();
+        let statuses: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(statuses[0]["state"], "clean");
+    }
+}
+
+#[test]
+fn test_edit_rehomes_project() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+
+    let project = |name: &str| -> serde_json::Value {
+        let output = pm(&config, &["list", "-o", "json", &format!("name:{}", name)])
+            .output()
+            .unwrap();
+        let projects: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        projects[0].clone()
+    };
+    let home_path = |rest: &str| dir.path().join(rest).display().to_string();
+
+    pm(
+        &config,
+        &["create", "api", "-r", "https://github.com/acme/api.git"],
+    )
+    .assert()
+    .success();
+    assert_eq!(
+        project("api")["local_path"],
+        home_path("github.com/acme/api")
+    );
+
+    pm(
+        &config,
+        &["edit", "api", "-r", "https://gitlab.com/acme/api.git"],
+    )
+    .assert()
+    .success();
+    assert_eq!(project("api")["hosting"], "gitlab");
+    assert_eq!(
+        project("api")["local_path"],
+        home_path("gitlab.com/acme/api")
+    );
+
+    pm(&config, &["edit", "api", "-H", "github"])
+        .assert()
+        .success();
+    assert_eq!(project("api")["hosting"], "github");
+    assert_eq!(
+        project("api")["local_path"],
+        home_path("github.com/acme/api")
+    );
+
+    // Without a URL there is nothing to derive a path from.
+    pm(&config, &["create", "notes", "-p", "/tmp/notes"])
+        .assert()
+        .success();
+    pm(&config, &["edit", "notes", "-H", "github"])
+        .assert()
+        .success();
+    assert_eq!(project("notes")["hosting"], "github");
+    assert_eq!(project("notes")["local_path"], "/tmp/notes");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_config_save_keeps_symlink_and_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let dotfiles = dir.path().join("dotfiles");
+    std::fs::create_dir_all(&dotfiles).unwrap();
+    let target = dotfiles.join("projects.toml");
+
+    pm(&target, &["init"]).assert().success();
+    std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o600)).unwrap();
+    std::os::unix::fs::symlink(&target, &config).unwrap();
+
+    pm(&config, &["create", "api", "-p", "/tmp/api"])
+        .assert()
+        .success();
+
+    assert!(config.symlink_metadata().unwrap().file_type().is_symlink());
+    assert!(std::fs::read_to_string(&target)
+        .unwrap()
+        .contains("name = \"api\""));
+    let mode = std::fs::metadata(&target).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn test_config_backups_rotate_and_restore() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+
+    pm(&config, &["init"]).assert().success();
+    let content = std::fs::read_to_string(&config).unwrap();
+    std::fs::write(
+        &config,
+        content.replace("max_backups = 10", "max_backups = 2"),
+    )
+    .unwrap();
+
+    for name in ["a", "b", "c"] {
+        pm(&config, &["create", name, "-p", &format!("/tmp/{}", name)])
+            .assert()
+            .success();
+    }
+
+    let backups: Vec<_> = std::fs::read_dir(dir.path().join("backups"))
+        .unwrap()
+        .collect();
+    assert_eq!(backups.len(), 2);
+
+    // The newest backup is the config from before "c" was added.
+    pm(&config, &["config", "restore", "1", "--force"])
+        .assert()
+        .success();
+    let content = std::fs::read_to_string(&config).unwrap();
+    assert!(content.contains("name = \"b\""));
+    assert!(!content.contains("name = \"c\""));
+
+    // Restoring backed up the replaced config, so it can be undone.
+    pm(&config, &["config", "restore", "--force"])
+        .assert()
+        .success();
+    let content = std::fs::read_to_string(&config).unwrap();
+    assert!(content.contains("name = \"c\""));
+
+    pm(&config, &["config", "restore", "9", "--force"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Backup '9' not found"));
+}
+
+#[test]
+fn test_config_lock_serializes_writers() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    pm(&config, &["init"]).assert().success();
+
+    // While another process holds the lock, a writer waits for it.
+    let lock = std::fs::File::create(dir.path().join("projects.toml.lock")).unwrap();
+    lock.lock().unwrap();
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_pm"))
+        .env("HOME", dir.path())
+        .env("PM_CONFIG", &config)
+        .args(["-q", "create", "waiting", "-p", "/tmp/waiting"])
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    assert!(child.try_wait().unwrap().is_none());
+    drop(lock);
+    assert!(child.wait().unwrap().success());
+
+    // Concurrent writers all get their change in.
+    let children: Vec<_> = (0..8)
+        .map(|i| {
+            std::process::Command::new(env!("CARGO_BIN_EXE_pm"))
+                .env("HOME", dir.path())
+                .env("PM_CONFIG", &config)
+                .args([
+                    "-q",
+                    "create",
+                    &format!("p{}", i),
+                    "-p",
+                    &format!("/tmp/p{}", i),
+                ])
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let content = std::fs::read_to_string(&config).unwrap();
+    for i in 0..8 {
+        assert!(content.contains(&format!("name = \"p{}\"", i)));
+    }
+    assert!(content.contains("name = \"waiting\""));
+}
+
+#[test]
+fn test_config_version_upgrade() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let wt_path = dir.path().join("wt");
+    std::fs::create_dir_all(wt_path.join("develop/.git")).unwrap();
+
+    let legacy = format!(
+        r#"version = "1.0"
+
+[settings]
+default_output_format = "table"
+
+[hostings.custom]
+base_path = "~/git"
+url_pattern = ""
+
+[[projects]]
+name = "wt"
+is_worktree = true
+hosting = "custom"
+local_path = "{}"
+created_at = "2026-01-01T00:00:00Z"
+last_updated = "2026-01-01T00:00:00Z"
+"#,
+        wt_path.display()
+    );
+    std::fs::write(&config, &legacy).unwrap();
+
+    pm(&config, &["list"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("from version 1.0 to 1.1"));
+
+    let content = std::fs::read_to_string(&config).unwrap();
+    assert!(content.contains("version = \"1.1\""));
+    assert!(content.contains("worktree_branch = \"develop\""));
+
+    // The file as it was before the upgrade is kept outside the rotation.
+    let original = dir.path().join("projects.toml.v1.0");
+    assert_eq!(std::fs::read_to_string(original).unwrap(), legacy);
+    assert!(!dir.path().join("backups").exists());
+}
+
+#[test]
+fn test_config_upgrade_finds_main_checkout() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    // `feature` sorts first but is a linked worktree with a `.git` file.
+    let wt_path = dir.path().join("wt");
+    std::fs::create_dir_all(wt_path.join("main/.git")).unwrap();
+    std::fs::create_dir_all(wt_path.join("feature")).unwrap();
+    std::fs::write(
+        wt_path.join("feature/.git"),
+        "gitdir: ../main/.git/worktrees/feature\n",
+    )
+    .unwrap();
+    // Two main checkouts can't be told apart.
+    let twin_path = dir.path().join("twin");
+    std::fs::create_dir_all(twin_path.join("a/.git")).unwrap();
+    std::fs::create_dir_all(twin_path.join("b/.git")).unwrap();
+    // The bare layout records its default branch in `.bare/HEAD`.
+    let bare_path = dir.path().join("bare");
+    std::fs::create_dir_all(bare_path.join(".bare")).unwrap();
+    std::fs::create_dir_all(bare_path.join("trunk")).unwrap();
+    std::fs::write(bare_path.join(".bare/HEAD"), "ref: refs/heads/trunk\n").unwrap();
+    std::fs::write(
+        bare_path.join("trunk/.git"),
+        "gitdir: ../.bare/worktrees/trunk\n",
+    )
+    .unwrap();
+
+    let mut legacy = String::from(
+        r#"version = "1.0"
+
+[settings]
+default_output_format = "table"
+
+[hostings.custom]
+base_path = "~/git"
+url_pattern = ""
+"#,
+    );
+    for (name, path) in [("wt", &wt_path), ("twin", &twin_path), ("bare", &bare_path)] {
+        legacy.push_str(&format!(
+            r#"
+[[projects]]
+name = "{}"
+is_worktree = true
+hosting = "custom"
+local_path = "{}"
+created_at = "2026-01-01T00:00:00Z"
+last_updated = "2026-01-01T00:00:00Z"
+"#,
+            name,
+            path.display()
+        ));
+    }
+    std::fs::write(&config, &legacy).unwrap();
+
+    pm(&config, &["list"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains(
+            "main checkout of worktree project twin",
+        ));
+
+    let content = std::fs::read_to_string(&config).unwrap();
+    assert!(content.contains("worktree_branch = \"main\""));
+    assert!(content.contains("worktree_branch = \"trunk\""));
+    assert_eq!(content.matches("worktree_branch").count(), 2);
+}
+
+#[test]
+fn test_newer_config_version_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let newer = "version = \"2.0\"\n\n[settings]\ndefault_output_format = \"table\"\n";
+    std::fs::write(&config, newer).unwrap();
+
+    pm(&config, &["create", "api", "-p", "/tmp/api"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("version 2.0 is newer"));
+
+    assert_eq!(std::fs::read_to_string(&config).unwrap(), newer);
+    assert!(!dir.path().join("backups").exists());
+}
+
+#[test]
+fn test_update_keeps_conflicting_autostash() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    init_repo(&origin);
+    let url = format!("file://{}", origin.display());
+    let app = dir.path().join("app");
+
+    pm(
+        &config,
+        &[
+            "create",
+            "app",
+            "-r",
+            &url,
+            "-p",
+            app.to_str().unwrap(),
+            "--clone",
+        ],
+    )
+    .assert()
+    .success();
+
+    let upstream = commit_file(&origin, "main", "README", "upstream\n");
+    std::fs::write(app.join("README"), "local\n").unwrap();
+
+    pm(&config, &["update", "--autostash"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("kept in stash@{0}"));
+
+    let repo = git2::Repository::open(&app).unwrap();
+    assert_eq!(repo.head().unwrap().target(), Some(upstream));
+    assert_eq!(stash_count(&app), 1);
+
+    // A conflicted checkout is not updated again.
+    pm(&config, &["update", "--autostash"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("unresolved conflicts"));
+    assert_eq!(stash_count(&app), 1);
+}
+
+#[test]
+fn test_update_skips_dirty_checkouts() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    init_repo(&origin);
+    let url = format!("file://{}", origin.display());
+    let app = dir.path().join("app");
+
+    pm(
+        &config,
+        &[
+            "create",
+            "app",
+            "-r",
+            &url,
+            "-p",
+            app.to_str().unwrap(),
+            "--clone",
+        ],
+    )
+    .assert()
+    .success();
+    let head = git2::Repository::open(&app)
+        .unwrap()
+        .head()
+        .unwrap()
+        .target();
+
+    commit_file(&origin, "main", "README", "upstream\n");
+    std::fs::write(app.join("README"), "local\n").unwrap();
+
+    pm(&config, &["update"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("app - skipped (dirty)"));
+
+    let repo = git2::Repository::open(&app).unwrap();
+    assert_eq!(repo.head().unwrap().target(), head);
+    assert_eq!(
+        std::fs::read_to_string(app.join("README")).unwrap(),
+        "local\n"
+    );
+}
+
+#[test]
+fn test_update_never_overwrites_untracked_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    init_repo(&origin);
+    let url = format!("file://{}", origin.display());
+    let app = dir.path().join("app");
+
+    pm(
+        &config,
+        &[
+            "create",
+            "app",
+            "-r",
+            &url,
+            "-p",
+            app.to_str().unwrap(),
+            "--clone",
+        ],
+    )
+    .assert()
+    .success();
+    let head = git2::Repository::open(&app)
+        .unwrap()
+        .head()
+        .unwrap()
+        .target();
+
+    commit_file(&origin, "main", "notes", "upstream\n");
+    std::fs::write(app.join("notes"), "mine\n").unwrap();
+
+    pm(&config, &["update"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "Update would overwrite local files: notes",
+        ));
+
+    let repo = git2::Repository::open(&app).unwrap();
+    assert_eq!(repo.head().unwrap().target(), head);
+    assert_eq!(
+        std::fs::read_to_string(app.join("notes")).unwrap(),
+        "mine\n"
+    );
+}
+
+fn head_commit(path: &Path) -> git2::Oid {
+    let repo = git2::Repository::open(path).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    head.id()
+}
+
+#[test]
+fn test_update_follows_branch_upstream() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    let origin_repo = init_repo(&origin);
+    let init = origin_repo.head().unwrap().peel_to_commit().unwrap();
+    origin_repo.branch("other", &init, false).unwrap();
+    let url = format!("file://{}", origin.display());
+    let app = dir.path().join("app");
+
+    pm(
+        &config,
+        &[
+            "create",
+            "app",
+            "-r",
+            &url,
+            "-p",
+            app.to_str().unwrap(),
+            "--clone",
+        ],
+    )
+    .assert()
+    .success();
+    {
+        let repo = git2::Repository::open(&app).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let mut branch = repo.branch("other", &head, false).unwrap();
+        branch.set_upstream(Some("origin/other")).unwrap();
+        repo.set_head("refs/heads/other").unwrap();
+    }
+
+    // `main` moving on does not concern a branch tracking `other`.
+    commit_file(&origin, "main", "README", "main\n");
+    pm(&config, &["update"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Updated app (up to date)"));
+    assert_eq!(head_commit(&app), init.id());
+
+    let other = commit_file(&origin, "other", "README", "other\n");
+    pm(&config, &["update"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Updated app (fast-forward)"));
+    assert_eq!(head_commit(&app), other);
+}
+
+#[test]
+fn test_update_uses_non_origin_remote() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    init_repo(&origin);
+    let url = format!("file://{}", origin.display());
+    let app = dir.path().join("app");
+
+    pm(
+        &config,
+        &[
+            "create",
+            "app",
+            "-r",
+            &url,
+            "-p",
+            app.to_str().unwrap(),
+            "--clone",
+        ],
+    )
+    .assert()
+    .success();
+    git2::Repository::open(&app)
+        .unwrap()
+        .remote_rename("origin", "upstream")
+        .unwrap();
+
+    let upstream = commit_file(&origin, "main", "README", "upstream\n");
+    pm(&config, &["update"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Updated app (fast-forward)"));
+    assert_eq!(head_commit(&app), upstream);
+}
+
+#[test]
+fn test_update_skips_detached_and_untracked_branches() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    init_repo(&origin);
+    let url = format!("file://{}", origin.display());
+    let detached = dir.path().join("detached");
+    let topic = dir.path().join("topic");
+
+    for path in [&detached, &topic] {
+        let name = path.file_name().unwrap().to_str().unwrap();
+        pm(
+            &config,
+            &[
+                "create",
+                name,
+                "-r",
+                &url,
+                "-p",
+                path.to_str().unwrap(),
+                "--clone",
+            ],
+        )
+        .assert()
+        .success();
+    }
+    {
+        let repo = git2::Repository::open(&detached).unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.set_head_detached(head).unwrap();
+
+        let repo = git2::Repository::open(&topic).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("topic", &head, false).unwrap();
+        repo.set_head("refs/heads/topic").unwrap();
+    }
+    let init = head_commit(&topic);
+
+    commit_file(&origin, "main", "README", "upstream\n");
+    pm(&config, &["update"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "detached - skipped (detached HEAD)",
+        ))
+        .stdout(predicates::str::contains("topic - skipped (no upstream)"));
+    assert_eq!(head_commit(&detached), init);
+    assert_eq!(head_commit(&topic), init);
+}
+
+#[test]
+fn test_update_checks_out_unborn_branch() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    init_repo(&origin);
+    let url = format!("file://{}", origin.display());
+    let app = dir.path().join("app");
+
+    {
+        let repo = git2::Repository::init(&app).unwrap();
+        repo.remote("origin", &url).unwrap();
+        let mut git_config = repo.config().unwrap();
+        git_config.set_str("branch.main.remote", "origin").unwrap();
+        git_config
+            .set_str("branch.main.merge", "refs/heads/main")
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+    }
+    pm(
+        &config,
+        &["create", "app", "-r", &url, "-p", app.to_str().unwrap()],
+    )
+    .assert()
+    .success();
+
+    pm(&config, &["update"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Updated app (fast-forward)"));
+    assert_eq!(head_commit(&app), head_commit(&origin));
+    assert_eq!(
+        std::fs::read_to_string(app.join("README")).unwrap(),
+        "hello\n"
+    );
+}
+
+#[test]
+fn test_update_autostash_on_unborn_branch() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    init_repo(&origin);
+    let url = format!("file://{}", origin.display());
+    let app = dir.path().join("app");
+
+    {
+        let repo = git2::Repository::init(&app).unwrap();
+        repo.remote("origin", &url).unwrap();
+        let mut git_config = repo.config().unwrap();
+        git_config.set_str("branch.main.remote", "origin").unwrap();
+        git_config
+            .set_str("branch.main.merge", "refs/heads/main")
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        std::fs::write(app.join("notes"), "staged\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("notes")).unwrap();
+        index.write().unwrap();
+    }
+    pm(
+        &config,
+        &["create", "app", "-r", &url, "-p", app.to_str().unwrap()],
+    )
+    .assert()
+    .success();
+
+    pm(&config, &["update", "--autostash"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Updated app (fast-forward)"));
+    assert_eq!(head_commit(&app), head_commit(&origin));
+    assert_eq!(
+        std::fs::read_to_string(app.join("notes")).unwrap(),
+        "staged\n"
+    );
+}
+
+#[test]
+fn test_update_covers_all_worktrees() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    let origin_repo = init_repo(&origin);
+    let init = origin_repo.head().unwrap().peel_to_commit().unwrap();
+    origin_repo.branch("other", &init, false).unwrap();
+    let url = format!("file://{}", origin.display());
+    let wt = dir.path().join("wt");
+
+    pm(
+        &config,
+        &[
+            "create",
+            "wt",
+            "-r",
+            &url,
+            "-p",
+            wt.to_str().unwrap(),
+            "-w",
+            "--clone",
+        ],
+    )
+    .assert()
+    .success();
+    pm(&config, &["worktree", "add", "wt", "other"])
+        .assert()
+        .success();
+
+    commit_file(&origin, "main", "README", "main\n");
+    commit_file(&origin, "other", "README", "other\n");
+    pm(&config, &["update"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Updated wt [main] (fast-forward)",
+        ))
+        .stdout(predicates::str::contains(
+            "Updated wt [other] (fast-forward)",
+        ));
+
+    pm(&config, &["update", "-b", "other"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Updated wt [other] (up to date)"))
+        .stdout(predicates::prelude::PredicateBooleanExt::not(
+            predicates::str::contains("wt [main]"),
+        ));
+}
+
+#[test]
+fn test_update_aborts_failed_rebase() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let origin = dir.path().join("origin");
+    init_repo(&origin);
+    let url = format!("file://{}", origin.display());
+    let app = dir.path().join("app");
+
+    pm(
+        &config,
+        &[
+            "create",
+            "app",
+            "-r",
+            &url,
+            "-p",
+            app.to_str().unwrap(),
+            "--clone",
+        ],
+    )
+    .assert()
+    .success();
+
+    // Local history adds `notes` and removes it again, so replaying the first
+    // commit trips over the untracked `notes` below.
+    commit_file(&app, "main", "notes", "tracked\n");
+    let repo = git2::Repository::open(&app).unwrap();
+    let parent = repo.head().unwrap().peel_to_commit().unwrap();
+    let mut builder = repo.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+    builder.remove("notes").unwrap();
+    let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+    let signature = git2::Signature::now("pm", "pm@localhost").unwrap();
+    let local = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Remove notes",
+            &tree,
+            &[&parent],
+        )
+        .unwrap();
+    repo.reset(
+        &repo.find_object(local, None).unwrap(),
+        git2::ResetType::Hard,
+        None,
+    )
+    .unwrap();
+
+    commit_file(&origin, "main", "upstream", "upstream\n");
+    std::fs::write(app.join("notes"), "untracked\n").unwrap();
+    std::fs::write(app.join("README"), "local\n").unwrap();
+
+    pm(&config, &["update", "-s", "rebase", "--autostash"])
+        .assert()
+        .failure();
+
+    assert_eq!(repo.state(), git2::RepositoryState::Clean);
+    assert_eq!(head_commit(&app), local);
+    // The autostash went back onto the untouched branch.
+    assert_eq!(
+        std::fs::read_to_string(app.join("README")).unwrap(),
+        "local\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(app.join("notes")).unwrap(),
+        "untracked\n"
+    );
+}
+
 #[test]
-fn test_placeholder() {
-    assert!(true);
+fn test_completion_honours_config_flag() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("projects.toml");
+    let team = dir.path().join("team.toml");
+    pm(&config, &["create", "mine", "-p", "/tmp/mine"])
+        .assert()
+        .success();
+    pm(&team, &["create", "shared", "-p", "/tmp/shared"])
+        .assert()
+        .success();
+
+    pm(
+        &config,
+        &["--", "pm", "-c", team.to_str().unwrap(), "delete", ""],
+    )
+    .env("COMPLETE", "bash")
+    .env("_CLAP_COMPLETE_INDEX", "4")
+    .env("_CLAP_COMPLETE_COMP_TYPE", "9")
+    .env("_CLAP_COMPLETE_SPACE", "true")
+    .env("_CLAP_IFS", "\n")
+    .assert()
+    .success()
+    .stdout(predicates::str::contains("shared"))
+    .stdout(predicates::prelude::PredicateBooleanExt::not(
+        predicates::str::contains("mine"),
+    ));
 }